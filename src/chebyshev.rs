@@ -0,0 +1,76 @@
+use std::f64::consts::PI;
+
+use crate::{Interval, functions::{basis_to_monomial, TargetFunction}};
+
+/// calculates the coefficients of the Chebyshev approximation of degree poly_deg.
+/// f is sampled in the n = poly_deg + 1 Chebyshev points t_j = cos(pi * (j + 0.5) / n)
+/// and the coefficients follow from the discrete cosine relation
+/// ### c_k = 2 / n * sum(f(x(t_j)) * cos(pi * k * (j + 0.5) / n)), c_0 is halved
+/// The result interpolates f in those points.
+/// https://en.wikipedia.org/wiki/Chebyshev_nodes
+pub fn chebyshev_coefficients(f: &dyn TargetFunction, poly_deg: usize, interval: Interval) -> Vec<f64> {
+    let n = poly_deg + 1;
+    let values: Vec<f64> = (0..n)
+    .map(|j| f.value(interval.from_unit((PI * (j as f64 + 0.5) / n as f64).cos())))
+    .collect();
+    let mut out: Vec<f64> = Vec::new();
+    for k in 0..n {
+        let mut sum = 0.;
+        for (j, value) in values.iter().enumerate() {
+            sum += value * (PI * k as f64 * (j as f64 + 0.5) / n as f64).cos();
+        }
+        out.push(2. / n as f64 * sum);
+    }
+    out[0] /= 2.;
+    out
+}
+
+/// Returns the value of the Chebyshev series sum(c_k * T_k(t)) in t from [-1, 1] using Clenshaw's recurrence
+/// ### b_k = c_k + 2t * b_{k+1} - b_{k+2}, value = c_0 + t * b_1 - b_2
+pub fn chebyshev_clenshaw(coeffs: &[f64], t: f64) -> f64 {
+    if coeffs.is_empty() {
+        return 0.;
+    }
+    let mut b1 = 0.;
+    let mut b2 = 0.;
+    for c in coeffs.iter().skip(1).rev() {
+        let b0 = c + 2. * t * b1 - b2;
+        b2 = b1;
+        b1 = b0;
+    }
+    coeffs[0] + t * b1 - b2
+}
+
+/// Returns the value of the Chebyshev approximation in point x from [a, b]
+pub fn chebyshev_approx_value(coeffs: &[f64], x: f64, interval: Interval) -> f64 {
+    chebyshev_clenshaw(coeffs, interval.to_unit(x))
+}
+
+/// Returns the coefficients of the Chebyshev polynomial T_deg, highest power first
+/// ### T_{n+1}(x) = 2x T_n(x) - T_{n-1}(x)
+pub fn chebyshev_polynomial(deg: usize) -> Vec<f64> {
+    // lowest power first while building
+    let mut previous: Vec<f64> = vec![1.];
+    if deg == 0 {
+        return previous;
+    }
+    let mut current: Vec<f64> = vec![0., 1.];
+    for _ in 1..deg {
+        let mut next = vec![0.; current.len() + 1];
+        for (k, elem) in current.iter().enumerate() {
+            next[k + 1] += 2. * elem;
+        }
+        for (k, elem) in previous.iter().enumerate() {
+            next[k] -= elem;
+        }
+        previous = current;
+        current = next;
+    }
+    current.reverse();
+    current
+}
+
+/// Returns the coefficients of the Chebyshev approximation in the original variable x, highest power first
+pub fn get_chebyshev_coefficients(coeffs: &[f64], interval: Interval) -> Vec<f64> {
+    basis_to_monomial(coeffs, chebyshev_polynomial, Some(interval))
+}
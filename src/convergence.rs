@@ -0,0 +1,229 @@
+use crate::{Interval, legendre::chop_coefficients};
+
+/// Relative size below which lambdas count as rounding noise and are left out of the fits
+const NOISE_FLOOR: f64 = 1e-13;
+
+/// Change of the local slope of ln e_k between neighbouring thirds of the fitted range above which the decay
+/// counts as faster than geometric. Entire functions steepen like ln k, by ln(3/2) and ln(4/3) between the thirds
+/// whatever their scale, a pole of order m only by about (m - 1/2) / k.
+const STEEPENING: f64 = 0.1;
+
+/// How the lambdas of a function fall off with the degree k
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decay {
+    /// |lambda_k| ~ k^power rho^(-k), f is analytic inside the Bernstein ellipse with parameter rho,
+    /// a pole of order m on the ellipse gives power m - 1/2
+    Geometric { rho: f64, power: f64 },
+    /// The decay keeps getting faster, no singularity detected (entire functions like exp).
+    /// rho is the rate at the end of the fitted range, which the lambdas only exceed further on.
+    SuperGeometric { rho: f64 },
+    /// |lambda_k| ~ k^(-order), f has a singularity on the interval (a kink or a jump)
+    Algebraic { order: f64 },
+}
+
+/// Result of `estimate_convergence`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvergenceEstimate {
+    pub decay: Decay,
+    /// ln of the fitted envelope at k = 0, relative to the largest lambda
+    pub intercept: f64,
+    /// Root mean square deviation of the fit from ln of the envelope
+    pub residual: f64,
+    /// First and last degree the fit was made on
+    pub fitted: (usize, usize),
+}
+
+impl ConvergenceEstimate {
+    /// Degree at which the fitted envelope drops below tolerance times the largest lambda,
+    /// roughly the degree the approximation needs for that relative error.
+    /// None when the fit doesn't decay (rho <= 1 or order <= 0) or the degree isn't finite.
+    pub fn predicted_degree(&self, tolerance: f64) -> Option<usize> {
+        let drop = self.intercept - tolerance.ln();
+        let degree = match self.decay {
+            Decay::Geometric { rho, .. } | Decay::SuperGeometric { rho } if rho <= 1. => return None,
+            Decay::Algebraic { order } if order <= 0. => return None,
+            // fixed point of k = (drop + power ln k) / ln(rho), the iteration contracts once k ln(rho) > |power|
+            Decay::Geometric { rho, power } => (0..50).fold(drop / rho.ln(), |k, _| (drop + power * k.max(1.).ln()) / rho.ln()),
+            Decay::SuperGeometric { rho } => drop / rho.ln(),
+            Decay::Algebraic { order } => (drop / order).exp(),
+        };
+        match degree.is_finite() {
+            true => Some(degree.max(0.).ceil() as usize),
+            false => None,
+        }
+    }
+
+    /// Points where the Bernstein ellipse crosses the real axis, mapped from t to x.
+    /// The nearest singularity of f lies on the ellipse, at one of these points when it is real.
+    /// ### t = +-(rho + 1 / rho) / 2
+    /// None for algebraic decay, whose singularity is on the interval itself, and when no singularity was detected.
+    pub fn singularity_bounds(&self, interval: Interval) -> Option<(f64, f64)> {
+        match self.decay {
+            Decay::Geometric { rho, .. } => {
+                let t = 0.5 * (rho + 1. / rho);
+                Some((interval.from_unit(-t), interval.from_unit(t)))
+            },
+            Decay::SuperGeometric { .. } | Decay::Algebraic { .. } => None,
+        }
+    }
+
+    /// Largest imaginary part on the ellipse in the original variable x
+    /// ### (rho - 1 / rho) / 2 * (b - a) / 2
+    pub fn singularity_height(&self, interval: Interval) -> Option<f64> {
+        match self.decay {
+            Decay::Geometric { rho, .. } => Some(0.5 * (rho - 1. / rho) * 0.5 * interval.width()),
+            Decay::SuperGeometric { .. } | Decay::Algebraic { .. } => None,
+        }
+    }
+}
+
+/// Classifies the decay of the lambdas of an approximation, best computed to a high degree with `gauss_lambdas`.
+/// The monotone envelope max_{j >= k} |lambda_j| (odd or even functions have zero lambdas) is fitted
+/// in the upper two thirds of the lambdas above the noise floor and before their plateau, see `chop_coefficients`, by
+/// ### ln e_k = c - k ln(rho)       (geometric)
+/// ### ln e_k = c - order ln(k)     (algebraic)
+/// in the least-squares sense, and the fit with the smaller residual wins.
+/// Geometric decay is then refitted with the power of k a pole of higher order brings,
+/// ### ln e_k = c + power ln(k) - k ln(rho)
+/// When the local slope steepens from third to third of the range, f has no singularity to find and
+/// the slope of the last third is reported.
+/// Returns None when fewer than 4 lambdas are above the noise floor, which polynomials of low degree are.
+/// https://en.wikipedia.org/wiki/Bernstein%27s_theorem_(approximation_theory)
+pub fn estimate_convergence(lambdas: &[f64]) -> Option<ConvergenceEstimate> {
+    let scale = lambdas.iter().fold(0., |max: f64, l| max.max(l.abs()));
+    if scale == 0. {
+        return None;
+    }
+    let mut envelope: Vec<f64> = lambdas.iter().map(|l| l.abs() / scale).collect();
+    for k in (0..envelope.len().saturating_sub(1)).rev() {
+        envelope[k] = envelope[k].max(envelope[k + 1]);
+    }
+    // rounding noise above the floor still ends the fitted range where it forms a plateau
+    let mut last = envelope.iter().rposition(|e| *e >= NOISE_FLOOR)?;
+    if let Some(length) = chop_coefficients(lambdas, NOISE_FLOOR) {
+        last = last.min(length.saturating_sub(1));
+    }
+    let first = (last / 3).max(1);
+    if last < first + 3 {
+        return None;
+    }
+
+    let k: Vec<f64> = (first..=last).map(|k| k as f64).collect();
+    let ln_e: Vec<f64> = envelope[first..=last].iter().map(|e| e.ln()).collect();
+    let ln_k: Vec<f64> = k.iter().map(|k| k.ln()).collect();
+    let geometric_residual = fit_line(&k, &ln_e).2;
+    let (algebraic_intercept, algebraic_slope, algebraic_residual) = fit_line(&ln_k, &ln_e);
+
+    let third = k.len() / 3;
+    if third >= 2 {
+        let slopes: Vec<(f64, f64, f64)> = [0, third, k.len() - third].iter().map(|start| fit_line(&k[*start..(start + third)], &ln_e[*start..(start + third)])).collect();
+        if slopes[1].1 < slopes[0].1 - STEEPENING && slopes[2].1 < slopes[1].1 - STEEPENING {
+            let (intercept, slope, residual) = slopes[2];
+            return Some(ConvergenceEstimate { decay: Decay::SuperGeometric { rho: (-slope).exp() }, intercept, residual, fitted: (first, last) });
+        }
+    }
+
+    let estimate = match geometric_residual <= algebraic_residual {
+        true => {
+            let (intercept, power, slope, residual) = fit_pole(&k, &ln_e);
+            ConvergenceEstimate {
+                decay: Decay::Geometric { rho: (-slope).exp(), power },
+                intercept,
+                residual,
+                fitted: (first, last),
+            }
+        },
+        false => ConvergenceEstimate {
+            decay: Decay::Algebraic { order: -algebraic_slope },
+            intercept: algebraic_intercept,
+            residual: algebraic_residual,
+            fitted: (first, last),
+        },
+    };
+    Some(estimate)
+}
+
+/// Least-squares line y = c + s x, returns (c, s, root mean square residual)
+fn fit_line(x: &[f64], y: &[f64]) -> (f64, f64, f64) {
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let sxx: f64 = x.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = x.iter().zip(y.iter()).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let residual = (x.iter().zip(y.iter()).map(|(x, y)| (y - intercept - slope * x).powi(2)).sum::<f64>() / n).sqrt();
+    (intercept, slope, residual)
+}
+
+/// Least-squares fit y = c + p ln(x) + s x, returns (c, p, s, root mean square residual)
+fn fit_pole(x: &[f64], y: &[f64]) -> (f64, f64, f64, f64) {
+    let n = x.len() as f64;
+    let ln_x: Vec<f64> = x.iter().map(|x| x.ln()).collect();
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / n;
+    let (mean_u, mean_x, mean_y) = (mean(&ln_x), mean(x), mean(y));
+    let dot = |a: &[f64], mean_a: f64, b: &[f64], mean_b: f64| -> f64 { a.iter().zip(b.iter()).map(|(a, b)| (a - mean_a) * (b - mean_b)).sum() };
+    // normal equations of the centered problem in (p, s)
+    let (suu, sux, sxx) = (dot(&ln_x, mean_u, &ln_x, mean_u), dot(&ln_x, mean_u, x, mean_x), dot(x, mean_x, x, mean_x));
+    let (suy, sxy) = (dot(&ln_x, mean_u, y, mean_y), dot(x, mean_x, y, mean_y));
+    let det = suu * sxx - sux * sux;
+    let power = (sxx * suy - sux * sxy) / det;
+    let slope = (suu * sxy - sux * suy) / det;
+    let intercept = mean_y - power * mean_u - slope * mean_x;
+    let residual = (x.iter().zip(ln_x.iter()).zip(y.iter()).map(|((x, u), y)| (y - intercept - power * u - slope * x).powi(2)).sum::<f64>() / n).sqrt();
+    (intercept, power, slope, residual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{functions::{perfect_fit, SimpleFunction}, legendre::gauss_lambdas};
+
+    #[test]
+    fn double_pole_is_found() {
+        let f = SimpleFunction { name: "Perfect Fit", value: perfect_fit, derivative: None, breakpoints: &[] };
+        // the pole at x = 3 is at t = 3 and t = 1.4, on the ellipses with rho = t + sqrt(t^2 - 1)
+        for (interval, rho_pole) in [(Interval::new(-1., 1.), 3. + 8f64.sqrt()), (Interval::new(0., 2.5), 1.4 + 0.96f64.sqrt())] {
+            let estimate = estimate_convergence(&gauss_lambdas(&f, 100, interval, 404)).unwrap();
+            match estimate.decay {
+                Decay::Geometric { rho, power } => {
+                    assert!((rho - rho_pole).abs() < 0.02 * rho_pole, "rho = {}", rho);
+                    assert!((power - 1.5).abs() < 0.5, "power = {}", power);
+                },
+                decay => panic!("{:?}", decay),
+            }
+            let (_, right) = estimate.singularity_bounds(interval).unwrap();
+            assert!((right - 3.).abs() < 0.05, "right = {}", right);
+        }
+    }
+
+    #[test]
+    fn entire_functions_have_no_singularity() {
+        for value in [f64::exp as fn(f64) -> f64, f64::sin, f64::cosh] {
+            let f = SimpleFunction { name: "Entire", value, derivative: None, breakpoints: &[] };
+            for interval in [Interval::new(-1., 1.), Interval::new(-4., 6.)] {
+                let estimate = estimate_convergence(&gauss_lambdas(&f, 100, interval, 404)).unwrap();
+                assert!(matches!(estimate.decay, Decay::SuperGeometric { .. }), "{:?}", estimate.decay);
+                assert!(estimate.singularity_bounds(interval).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn growing_envelope_has_no_prediction() {
+        let estimate = |decay| ConvergenceEstimate { decay, intercept: 0., residual: 0., fitted: (1, 10) };
+        assert_eq!(estimate(Decay::Geometric { rho: 0.9, power: 0. }).predicted_degree(1e-10), None);
+        assert_eq!(estimate(Decay::Geometric { rho: 1., power: 0. }).predicted_degree(1e-10), None);
+        assert_eq!(estimate(Decay::SuperGeometric { rho: f64::NAN }).predicted_degree(1e-10), None);
+        assert_eq!(estimate(Decay::Algebraic { order: -1. }).predicted_degree(1e-10), None);
+        assert_eq!(estimate(Decay::Algebraic { order: 1e-300 }).predicted_degree(1e-10), None);
+        assert_eq!(estimate(Decay::Geometric { rho: 10., power: 0. }).predicted_degree(1e-10), Some(10));
+    }
+
+    #[test]
+    fn kinks_decay_algebraically() {
+        let f = SimpleFunction { name: "Absolute", value: f64::abs, derivative: None, breakpoints: &[0.] };
+        let estimate = estimate_convergence(&gauss_lambdas(&f, 100, Interval::new(-1., 1.), 404)).unwrap();
+        assert!(matches!(estimate.decay, Decay::Algebraic { .. }), "{:?}", estimate.decay);
+    }
+}
@@ -0,0 +1,255 @@
+use std::{fmt, fs, path::Path};
+
+use crate::fit::Sample;
+
+/// Error of `parse_csv` and `read_csv`, line is 1-based, 0 when the error concerns the whole file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvError {
+    pub message: String,
+    pub line: usize,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "{} (line {})", self.message, line),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Character separating the fields of a row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Delimiter {
+    Comma,
+    Semicolon,
+    Tab,
+    /// Any run of spaces and tabs
+    Whitespace,
+}
+
+impl Delimiter {
+    pub const ALL: [Delimiter; 4] = [Delimiter::Comma, Delimiter::Semicolon, Delimiter::Tab, Delimiter::Whitespace];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "Comma",
+            Delimiter::Semicolon => "Semicolon",
+            Delimiter::Tab => "Tab",
+            Delimiter::Whitespace => "Whitespace",
+        }
+    }
+}
+
+/// Whether the first row holds column names
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Header {
+    /// The first row is a header when one of the selected columns isn't a number there
+    Detect,
+    Present,
+    Absent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: Delimiter,
+    pub header: Header,
+    /// Columns are counted from 0
+    pub x_column: usize,
+    pub y_column: usize,
+    /// Column with the weights of the samples, all weights are 1 without it
+    pub weight_column: Option<usize>,
+    /// Lines starting with this character are skipped
+    pub comment: char,
+}
+
+impl Default for CsvOptions {
+    fn default() -> CsvOptions {
+        CsvOptions {
+            delimiter: Delimiter::Comma,
+            header: Header::Detect,
+            x_column: 0,
+            y_column: 1,
+            weight_column: None,
+            comment: '#',
+        }
+    }
+}
+
+/// Samples read from a file, with the column names when it has a header
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvData {
+    pub headers: Vec<String>,
+    pub samples: Vec<Sample>,
+}
+
+/// Reads the samples from a CSV/TSV file, see `parse_csv`
+pub fn read_csv(path: impl AsRef<Path>, options: &CsvOptions) -> Result<CsvData, CsvError> {
+    let text = fs::read_to_string(path.as_ref()).map_err(|error| CsvError {
+        message: format!("can't read {}: {}", path.as_ref().display(), error),
+        line: 0,
+    })?;
+    parse_csv(&text, options)
+}
+
+/// Parses x, y and optionally weight columns from CSV/TSV text.
+/// Empty lines and comment lines are skipped, fields may be quoted with double quotes.
+/// Every other row has to contain numbers in the selected columns, weights have to be positive.
+pub fn parse_csv(text: &str, options: &CsvOptions) -> Result<CsvData, CsvError> {
+    let mut headers: Vec<String> = Vec::new();
+    let mut samples: Vec<Sample> = Vec::new();
+    let mut first_row = true;
+    let columns = [Some(options.x_column), Some(options.y_column), options.weight_column];
+    let needed = columns.iter().flatten().max().map_or(0, |max| max + 1);
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(options.comment) {
+            continue;
+        }
+        let fields = split_fields(trimmed, options.delimiter);
+        if first_row {
+            first_row = false;
+            let is_header = match options.header {
+                Header::Present => true,
+                Header::Absent => false,
+                Header::Detect => columns
+                    .iter()
+                    .flatten()
+                    .any(|column| fields.get(*column).is_none_or(|field| field.parse::<f64>().is_err())),
+            };
+            if is_header {
+                headers = fields;
+                continue;
+            }
+        }
+        if fields.len() < needed {
+            return Err(CsvError {
+                message: format!("expected at least {} columns, found {}", needed, fields.len()),
+                line: line_number,
+            });
+        }
+        let number = |column: usize| -> Result<f64, CsvError> {
+            match fields[column].parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(value),
+                _ => Err(CsvError {
+                    message: format!("column {}: '{}' is not a number", column, fields[column]),
+                    line: line_number,
+                }),
+            }
+        };
+        let weight = match options.weight_column {
+            Some(column) => {
+                let weight = number(column)?;
+                if weight <= 0. {
+                    return Err(CsvError { message: format!("column {}: non-positive weight {}", column, weight), line: line_number });
+                }
+                weight
+            },
+            None => 1.,
+        };
+        samples.push(Sample { x: number(options.x_column)?, y: number(options.y_column)?, weight });
+    }
+
+    if samples.is_empty() {
+        return Err(CsvError { message: String::from("no data rows"), line: 0 });
+    }
+    Ok(CsvData { headers, samples })
+}
+
+/// Splits a row at the delimiter. Double quotes group a field containing delimiters, "" inside them is a quote.
+fn split_fields(line: &str, delimiter: Delimiter) -> Vec<String> {
+    let separator = match delimiter {
+        Delimiter::Comma => ',',
+        Delimiter::Semicolon => ';',
+        Delimiter::Tab => '\t',
+        Delimiter::Whitespace => return line.split_whitespace().map(String::from).collect(),
+    };
+    let mut fields: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xy(data: &CsvData) -> Vec<(f64, f64, f64)> {
+        data.samples.iter().map(|sample| (sample.x, sample.y, sample.weight)).collect()
+    }
+
+    #[test]
+    fn detects_headers() {
+        let data = parse_csv("time,value\n0,1\n1,2.5\n", &CsvOptions::default()).unwrap();
+        assert_eq!(data.headers, vec!["time", "value"]);
+        assert_eq!(xy(&data), vec![(0., 1., 1.), (1., 2.5, 1.)]);
+
+        let data = parse_csv("0,1\n1,2.5\n", &CsvOptions::default()).unwrap();
+        assert!(data.headers.is_empty());
+        assert_eq!(data.samples.len(), 2);
+
+        // only the selected columns decide, a text column elsewhere doesn't make a header
+        let options = CsvOptions { x_column: 1, y_column: 2, ..CsvOptions::default() };
+        let data = parse_csv("a,0,1\nb,1,2\n", &options).unwrap();
+        assert!(data.headers.is_empty());
+        assert_eq!(xy(&data), vec![(0., 1., 1.), (1., 2., 1.)]);
+
+        let options = CsvOptions { header: Header::Present, ..CsvOptions::default() };
+        assert_eq!(parse_csv("0,1\n1,2\n", &options).unwrap().samples.len(), 1);
+    }
+
+    #[test]
+    fn quoted_fields() {
+        let options = CsvOptions { delimiter: Delimiter::Semicolon, ..CsvOptions::default() };
+        let data = parse_csv("\"x; in \"\"s\"\"\";\"y\"\n\"1\";2\n", &options).unwrap();
+        assert_eq!(data.headers, vec!["x; in \"s\"", "y"]);
+        assert_eq!(xy(&data), vec![(1., 2., 1.)]);
+    }
+
+    #[test]
+    fn skips_blank_and_comment_lines() {
+        let options = CsvOptions { delimiter: Delimiter::Whitespace, ..CsvOptions::default() };
+        let data = parse_csv("# measured\n\n  1   2\n   \n\t3\t4\n", &options).unwrap();
+        assert_eq!(xy(&data), vec![(1., 2., 1.), (3., 4., 1.)]);
+        assert_eq!(parse_csv("# nothing\n\n", &options).unwrap_err(), CsvError { message: String::from("no data rows"), line: 0 });
+    }
+
+    #[test]
+    fn malformed_rows() {
+        let e = parse_csv("x,y\n0,1\n2\n", &CsvOptions::default()).unwrap_err();
+        assert_eq!(e, CsvError { message: String::from("expected at least 2 columns, found 1"), line: 3 });
+        let e = parse_csv("0,1\n\n1,abc\n", &CsvOptions::default()).unwrap_err();
+        assert_eq!(e, CsvError { message: String::from("column 1: 'abc' is not a number"), line: 3 });
+        assert_eq!(e.to_string(), "column 1: 'abc' is not a number (line 3)");
+        assert_eq!(parse_csv("0,inf\n", &CsvOptions { header: Header::Absent, ..CsvOptions::default() }).unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn weights() {
+        let options = CsvOptions { weight_column: Some(2), ..CsvOptions::default() };
+        let data = parse_csv("x,y,w\n0,1,0.5\n1,2,2\n", &options).unwrap();
+        assert_eq!(xy(&data), vec![(0., 1., 0.5), (1., 2., 2.)]);
+        for weight in ["0", "-1"] {
+            let e = parse_csv(&format!("0,1,1\n1,2,{}\n", weight), &options).unwrap_err();
+            assert_eq!(e.line, 2);
+            assert!(e.message.contains("non-positive weight"), "{}", e.message);
+        }
+    }
+}
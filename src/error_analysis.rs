@@ -0,0 +1,145 @@
+use crate::{Interval, functions::TargetFunction, gauss::GaussKronrod, integral::{integrate_split, Quadrature}};
+
+/// Equally spaced points the maximum error is first searched in
+const SAMPLES: usize = 2000;
+
+/// Largest local maxima of the sampled error that get refined
+const REFINED_MAXIMA: usize = 5;
+
+/// Distances between a target function and its approximation on an interval
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorNorms {
+    /// ### sqrt(integral of (f - p)^2 from a to b)
+    pub l2: f64,
+    /// ### max |f - p| on [a, b]
+    pub max: f64,
+    /// Point where |f - p| is largest
+    pub argmax: f64,
+    /// l2 divided by the L2 norm of f, None when f vanishes
+    pub relative_l2: Option<f64>,
+    /// max divided by the largest |f|, None when f vanishes
+    pub relative_max: Option<f64>,
+}
+
+/// Which of the `ErrorNorms` a tolerance refers to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorNorm {
+    L2,
+    Max,
+    RelativeL2,
+    RelativeMax,
+}
+
+impl ErrorNorm {
+    pub const ALL: [ErrorNorm; 4] = [ErrorNorm::L2, ErrorNorm::Max, ErrorNorm::RelativeL2, ErrorNorm::RelativeMax];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorNorm::L2 => "L2",
+            ErrorNorm::Max => "Maximum",
+            ErrorNorm::RelativeL2 => "Relative L2",
+            ErrorNorm::RelativeMax => "Relative Maximum",
+        }
+    }
+
+    /// Picks this norm from norms, relative norms fall back to the absolute ones when f vanishes
+    pub fn of(&self, norms: &ErrorNorms) -> f64 {
+        match self {
+            ErrorNorm::L2 => norms.l2,
+            ErrorNorm::Max => norms.max,
+            ErrorNorm::RelativeL2 => norms.relative_l2.unwrap_or(norms.l2),
+            ErrorNorm::RelativeMax => norms.relative_max.unwrap_or(norms.max),
+        }
+    }
+}
+
+/// Quadrature for `error_norms`. Squared errors worth showing can be tiny, so the error estimate is judged
+/// against the integral itself, with an absolute floor for integrands that vanish.
+pub fn norm_quadrature() -> GaussKronrod {
+    GaussKronrod { tolerance: 1e-100, relative_tolerance: 1e-13, max_subdivisions: 200 }
+}
+
+/// Measures how far approx is from f on the interval.
+/// The L2 norms are integrated with the quadrature, split at the breakpoints of f and the given ones
+/// (element boundaries of a piecewise approximation for example), so kinks don't spoil the rule.
+/// The maximum is searched in 2000 equally spaced points and the breakpoints, then the largest
+/// local maxima are refined by golden-section search between their neighbours.
+/// Points where approx isn't finite (poles) count as an infinite error.
+/// * breakpoints - kinks of approx, the ones of f are added automatically
+pub fn error_norms(f: &dyn TargetFunction, approx: &dyn Fn(f64) -> f64, interval: Interval, breakpoints: &[f64], quadrature: &dyn Quadrature) -> ErrorNorms {
+    let error = |x: f64| -> f64 {
+        let e = (f.value(x) - approx(x)).abs();
+        if e.is_nan() { f64::INFINITY } else { e }
+    };
+    let mut splits = f.breakpoints();
+    splits.extend_from_slice(breakpoints);
+
+    let l2 = integrate_split(quadrature, &|x| error(x).powi(2), interval.a, interval.b, &splits).0.sqrt();
+    let f_l2 = integrate_split(quadrature, &|x| f.value(x).powi(2), interval.a, interval.b, &splits).0.sqrt();
+
+    let mut grid: Vec<f64> = (0..=SAMPLES).map(|i| interval.a + interval.width() * i as f64 / SAMPLES as f64).collect();
+    grid.extend(splits.iter().filter(|x| **x > interval.a && **x < interval.b));
+    grid.sort_by(|a, b| a.total_cmp(b));
+    let values: Vec<f64> = grid.iter().map(|x| error(*x)).collect();
+    let f_max = grid.iter().fold(0., |max: f64, x| max.max(f.value(*x).abs()));
+
+    // local maxima of the samples, largest first
+    let mut maxima: Vec<usize> = (0..grid.len())
+        .filter(|i| {
+            let left = if *i == 0 { f64::NEG_INFINITY } else { values[i - 1] };
+            let right = values.get(i + 1).copied().unwrap_or(f64::NEG_INFINITY);
+            values[*i] >= left && values[*i] >= right
+        })
+        .collect();
+    maxima.sort_by(|a, b| values[*b].total_cmp(&values[*a]));
+
+    let (mut argmax, mut max) = (grid[0], values[0]);
+    for i in maxima.into_iter().take(REFINED_MAXIMA) {
+        let (mut x, mut e) = (grid[i], values[i]);
+        if e.is_finite() {
+            let lo = grid[i.saturating_sub(1)];
+            let hi = grid[(i + 1).min(grid.len() - 1)];
+            let refined = golden_section_max(&error, lo, hi);
+            if error(refined) > e {
+                (x, e) = (refined, error(refined));
+            }
+        }
+        if e > max {
+            (argmax, max) = (x, e);
+        }
+    }
+
+    let relative = |norm: f64, f_norm: f64| if f_norm > 0. { Some(norm / f_norm) } else { None };
+    ErrorNorms {
+        l2,
+        max,
+        argmax,
+        relative_l2: relative(l2, f_l2),
+        relative_max: relative(max, f_max),
+    }
+}
+
+/// Returns the point of [lo, hi] where g is largest, assuming g is unimodal there
+pub fn golden_section_max(g: &dyn Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.) / 2.;
+    let mut x1 = hi - ratio * (hi - lo);
+    let mut x2 = lo + ratio * (hi - lo);
+    let mut g1 = g(x1);
+    let mut g2 = g(x2);
+    for _ in 0..60 {
+        if g1 < g2 {
+            lo = x1;
+            x1 = x2;
+            g1 = g2;
+            x2 = lo + ratio * (hi - lo);
+            g2 = g(x2);
+        } else {
+            hi = x2;
+            x2 = x1;
+            g2 = g1;
+            x1 = hi - ratio * (hi - lo);
+            g1 = g(x1);
+        }
+    }
+    0.5 * (lo + hi)
+}
@@ -0,0 +1,496 @@
+use std::{f64::consts::{E, PI}, fmt};
+
+use crate::functions::{TargetFunction, TargetFunction2D};
+
+/// Error of `Expression::parse`, position is the index of the character where parsing failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (at character {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Exp,
+    Log,
+    Abs,
+    Sqrt,
+    Pow,
+    Min,
+    Max,
+    If,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Func> {
+        match name {
+            "sin" => Some(Func::Sin),
+            "cos" => Some(Func::Cos),
+            "tan" => Some(Func::Tan),
+            "exp" => Some(Func::Exp),
+            "log" | "ln" => Some(Func::Log),
+            "abs" => Some(Func::Abs),
+            "sqrt" => Some(Func::Sqrt),
+            "pow" => Some(Func::Pow),
+            "min" => Some(Func::Min),
+            "max" => Some(Func::Max),
+            "if" => Some(Func::If),
+            _ => None,
+        }
+    }
+
+    fn arguments(&self) -> usize {
+        match self {
+            Func::Pow | Func::Min | Func::Max => 2,
+            Func::If => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// Syntax tree of a parsed expression
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    X,
+    Y,
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, x: f64, y: f64) -> f64 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::X => x,
+            Expr::Y => y,
+            Expr::Neg(inner) => -inner.eval(x, y),
+            Expr::Binary(op, left, right) => {
+                let l = left.eval(x, y);
+                let r = right.eval(x, y);
+                let truth = |b: bool| if b { 1. } else { 0. };
+                match op {
+                    BinaryOp::Add => l + r,
+                    BinaryOp::Sub => l - r,
+                    BinaryOp::Mul => l * r,
+                    BinaryOp::Div => l / r,
+                    BinaryOp::Pow => l.powf(r),
+                    BinaryOp::Less => truth(l < r),
+                    BinaryOp::LessEqual => truth(l <= r),
+                    BinaryOp::Greater => truth(l > r),
+                    BinaryOp::GreaterEqual => truth(l >= r),
+                    BinaryOp::Equal => truth(l == r),
+                    BinaryOp::NotEqual => truth(l != r),
+                }
+            },
+            Expr::Call(func, args) => {
+                // if only evaluates the chosen branch
+                if *func == Func::If {
+                    return match args[0].eval(x, y) != 0. {
+                        true => args[1].eval(x, y),
+                        false => args[2].eval(x, y),
+                    };
+                }
+                let a = args[0].eval(x, y);
+                match func {
+                    Func::Sin => a.sin(),
+                    Func::Cos => a.cos(),
+                    Func::Tan => a.tan(),
+                    Func::Exp => a.exp(),
+                    Func::Log => a.ln(),
+                    Func::Abs => a.abs(),
+                    Func::Sqrt => a.sqrt(),
+                    Func::Pow => a.powf(args[1].eval(x, y)),
+                    Func::Min => a.min(args[1].eval(x, y)),
+                    Func::Max => a.max(args[1].eval(x, y)),
+                    Func::If => unreachable!(),
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(&'static str),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+/// Splits the source into tokens, each paired with the position of its first character
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    const OPERATORS: [&str; 11] = ["<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "^"];
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // exponent like 1e-3
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text.parse::<f64>().map_err(|_| ParseError {
+                message: format!("invalid number '{}'", text),
+                position: start,
+            })?;
+            tokens.push((Token::Number(value), start));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect::<String>().to_lowercase()), start));
+        } else if c == '(' {
+            tokens.push((Token::LeftParen, start));
+            i += 1;
+        } else if c == ')' {
+            tokens.push((Token::RightParen, start));
+            i += 1;
+        } else if c == ',' {
+            tokens.push((Token::Comma, start));
+            i += 1;
+        } else {
+            let op = OPERATORS.iter().find(|op| {
+                op.chars().enumerate().all(|(k, oc)| chars.get(i + k) == Some(&oc))
+            });
+            match op {
+                Some(op) => {
+                    tokens.push((Token::Op(op), start));
+                    i += op.len();
+                },
+                None => return Err(ParseError { message: format!("unexpected character '{}'", c), position: start }),
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser, from the lowest precedence:
+/// ### comparison < + - < * / < unary minus < ^ < numbers, x, y, constants, calls, parentheses
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    current: usize,
+    end: usize,
+    /// whether y is a variable too
+    two_dimensional: bool,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.current).map(|t| &t.0)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.current).map(|t| t.1).unwrap_or(self.end)
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, ParseError> {
+        Err(ParseError { message: message.to_string(), position: self.position() })
+    }
+
+    fn next_op(&mut self, ops: &[&str]) -> Option<&'static str> {
+        if let Some(Token::Op(op)) = self.peek() {
+            if ops.contains(op) {
+                let op = *op;
+                self.current += 1;
+                return Some(op);
+            }
+        }
+        None
+    }
+
+    fn expect(&mut self, token: Token, message: &str) -> Result<(), ParseError> {
+        if self.peek() == Some(&token) {
+            self.current += 1;
+            Ok(())
+        } else {
+            self.error(message)
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let left = self.additive()?;
+        let op = match self.next_op(&["<", "<=", ">", ">=", "==", "!="]) {
+            Some("<") => BinaryOp::Less,
+            Some("<=") => BinaryOp::LessEqual,
+            Some(">") => BinaryOp::Greater,
+            Some(">=") => BinaryOp::GreaterEqual,
+            Some("==") => BinaryOp::Equal,
+            Some(_) => BinaryOp::NotEqual,
+            None => return Ok(left),
+        };
+        let right = self.additive()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.term()?;
+        while let Some(op) = self.next_op(&["+", "-"]) {
+            let right = self.term()?;
+            let op = if op == "+" { BinaryOp::Add } else { BinaryOp::Sub };
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.next_op(&["*", "/"]) {
+            let right = self.unary()?;
+            let op = if op == "*" { BinaryOp::Mul } else { BinaryOp::Div };
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        match self.next_op(&["+", "-"]) {
+            Some("-") => Ok(Expr::Neg(Box::new(self.unary()?))),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.primary()?;
+        if self.next_op(&["^"]).is_some() {
+            // right associative, 2^-x is allowed
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+        let token = match self.tokens.get(self.current) {
+            Some((token, _)) => token.clone(),
+            None => return self.error("unexpected end of expression"),
+        };
+        self.current += 1;
+        match token {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::LeftParen => {
+                let inner = self.comparison()?;
+                self.expect(Token::RightParen, "expected ')'")?;
+                Ok(inner)
+            },
+            Token::Ident(name) => {
+                match name.as_str() {
+                    "x" => return Ok(Expr::X),
+                    "y" if self.two_dimensional => return Ok(Expr::Y),
+                    "pi" => return Ok(Expr::Number(PI)),
+                    "e" => return Ok(Expr::Number(E)),
+                    _ => {},
+                }
+                let func = match Func::from_name(&name) {
+                    Some(func) => func,
+                    None => return Err(ParseError { message: format!("unknown name '{}'", name), position }),
+                };
+                self.expect(Token::LeftParen, &format!("expected '(' after '{}'", name))?;
+                let mut args = vec![self.comparison()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.current += 1;
+                    args.push(self.comparison()?);
+                }
+                self.expect(Token::RightParen, "expected ')' or ','")?;
+                if args.len() != func.arguments() {
+                    return Err(ParseError {
+                        message: format!("'{}' takes {} argument(s), got {}", name, func.arguments(), args.len()),
+                        position,
+                    });
+                }
+                Ok(Expr::Call(func, args))
+            },
+            Token::Op(op) => Err(ParseError { message: format!("unexpected '{}'", op), position }),
+            Token::RightParen => Err(ParseError { message: "unexpected ')'".to_string(), position }),
+            Token::Comma => Err(ParseError { message: "unexpected ','".to_string(), position }),
+        }
+    }
+}
+
+/// Function of x typed in by the user, for example "if(x < 0, -x, sin(pi * x)) + 0.5 * x^2".
+/// Supports + - * / ^, comparisons (true = 1, false = 0), parentheses, constants pi and e
+/// and the functions sin, cos, tan, exp, log (ln), abs, sqrt, pow, min, max and if(condition, then, else).
+/// Expressions from `parse_2d` may use y as well.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    root: Expr,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Expression, ParseError> {
+        Expression::parse_with(source, false)
+    }
+
+    /// Parses a function of x and y, see `eval_2d`
+    pub fn parse_2d(source: &str) -> Result<Expression, ParseError> {
+        Expression::parse_with(source, true)
+    }
+
+    fn parse_with(source: &str, two_dimensional: bool) -> Result<Expression, ParseError> {
+        let tokens = tokenize(source)?;
+        let end = source.chars().count();
+        let mut parser = Parser { tokens, current: 0, end, two_dimensional };
+        let root = parser.comparison()?;
+        if parser.current < parser.tokens.len() {
+            return parser.error("unexpected input after the end of expression");
+        }
+        Ok(Expression { source: source.to_string(), root })
+    }
+
+    pub fn eval(&self, x: f64) -> f64 {
+        self.root.eval(x, 0.)
+    }
+
+    /// Value of an expression from `parse_2d` in point (x, y)
+    pub fn eval_2d(&self, x: f64, y: f64) -> f64 {
+        self.root.eval(x, y)
+    }
+}
+
+impl TargetFunction for Expression {
+    fn name(&self) -> String {
+        self.source.clone()
+    }
+
+    fn value(&self, x: f64) -> f64 {
+        self.eval(x)
+    }
+}
+
+impl TargetFunction2D for Expression {
+    fn name(&self) -> String {
+        self.source.clone()
+    }
+
+    fn value(&self, x: f64, y: f64) -> f64 {
+        self.eval_2d(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, x: f64) -> f64 {
+        Expression::parse(source).unwrap().eval(x)
+    }
+
+    fn error(source: &str) -> ParseError {
+        Expression::parse(source).unwrap_err()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("-x^2", 3.), -9.);
+        assert_eq!(eval("2^-x", 1.), 0.5);
+        assert_eq!(eval("2^3^2", 0.), 512.);
+        assert_eq!(eval("1 + 2 * 3 - 4 / 2", 0.), 5.);
+        assert_eq!(eval("(1 + 2) * 3", 0.), 9.);
+        assert_eq!(eval("x + 1 < 2 * x", 2.), 1.);
+        assert_eq!(eval("x + 1 < 2 * x", 0.), 0.);
+    }
+
+    #[test]
+    fn numbers_constants_and_functions() {
+        assert_eq!(eval("1e-3 + 2.5E2", 0.), 250.001);
+        assert_eq!(eval("pi", 0.), PI);
+        assert_eq!(eval("e", 0.), E);
+        assert_eq!(eval("SIN(X)", 1.), 1f64.sin());
+        assert_eq!(eval("ln(x)", E), 1.);
+        assert_eq!(eval("pow(x, 3) + min(x, 1) + max(x, 1)", 2.), 11.);
+        assert_eq!(Expression::parse_2d("x * y - y").unwrap().eval_2d(3., 2.), 4.);
+    }
+
+    #[test]
+    fn if_takes_only_the_chosen_branch() {
+        let expression = Expression::parse("if(x < 0, sqrt(-x), log(x))").unwrap();
+        assert_eq!(expression.eval(-4.), 2.);
+        assert_eq!(expression.eval(E), 1.);
+        assert_eq!(eval("if(1, 1, 0 / 0)", 0.), 1.);
+        assert_eq!(eval("if(0, 0 / 0, 2)", 0.), 2.);
+    }
+
+    #[test]
+    fn wrong_arity() {
+        let e = error("sin(x, 2)");
+        assert_eq!(e.message, "'sin' takes 1 argument(s), got 2");
+        assert_eq!(e.position, 0);
+        let e = error("1 + max(x)");
+        assert_eq!(e.message, "'max' takes 2 argument(s), got 1");
+        assert_eq!(e.position, 4);
+        assert_eq!(error("if(x, 1)").message, "'if' takes 3 argument(s), got 2");
+    }
+
+    #[test]
+    fn unknown_identifiers() {
+        let e = error("2 * foo(x)");
+        assert_eq!(e.message, "unknown name 'foo'");
+        assert_eq!(e.position, 4);
+        // y is only a variable in two dimensions
+        assert_eq!(error("x + y").message, "unknown name 'y'");
+        assert_eq!(error("x + y").position, 4);
+        assert_eq!(error("sin x").message, "expected '(' after 'sin'");
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(error("x + * 2").position, 4);
+        assert_eq!(error("x $ 1").position, 2);
+        assert_eq!(error("x $ 1").message, "unexpected character '$'");
+        // the end of the source when the expression stops early
+        assert_eq!(error("(x + 1").position, 6);
+        assert_eq!(error("x + ").position, 4);
+        assert_eq!(error("x 2").position, 2);
+        assert_eq!(error("1..2").message, "invalid number '1..2'");
+        assert_eq!(error("x + * 2").to_string(), "unexpected '*' (at character 5)");
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{Interval, functions::TargetFunction, legendre::{legendre_approx_value, legendre_values}, linalg::{back_substitution, householder_qr, singular_values}};
+
+/// Measured point (x, y) with the weight of its squared residual
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub x: f64,
+    pub y: f64,
+    pub weight: f64,
+}
+
+impl Sample {
+    pub fn new(x: f64, y: f64) -> Sample {
+        Sample { x, y, weight: 1. }
+    }
+}
+
+/// Result of `fit_legendre`
+#[derive(Debug, Clone)]
+pub struct LeastSquaresFit {
+    /// Legendre coefficients, evaluate them with `legendre_approx_value` on the same interval
+    pub lambdas: Vec<f64>,
+    /// y - p(x) of every sample, in the order of the samples
+    pub residuals: Vec<f64>,
+    /// Weighted root mean square of the residuals
+    pub rms: f64,
+    /// Ratio of the largest and the smallest singular value of the weighted Legendre Vandermonde matrix
+    pub condition_number: f64,
+}
+
+/// Fits a polynomial of degree poly_deg to the samples in the weighted least-squares sense
+/// ### minimize sum(w_i * (y_i - sum(lambda_k * L_k(t_i)))^2)
+/// The Legendre Vandermonde matrix V_ik = sqrt(w_i) L_k(t_i) is factored with Householder QR,
+/// which avoids squaring its condition number like the normal equations would.
+/// Samples outside the interval are extrapolated.
+/// Returns None when there are fewer distinct points than coefficients.
+pub fn fit_legendre(samples: &[Sample], poly_deg: usize, interval: Interval) -> Option<LeastSquaresFit> {
+    let matrix: Vec<Vec<f64>> = samples
+        .iter()
+        .map(|sample| {
+            let root = sample.weight.sqrt();
+            legendre_values(poly_deg, interval.to_unit(sample.x)).iter().map(|v| root * v).collect()
+        })
+        .collect();
+    let rhs: Vec<f64> = samples.iter().map(|sample| sample.weight.sqrt() * sample.y).collect();
+    let (r, qtb) = householder_qr(matrix, rhs)?;
+
+    // R has the singular values of V
+    let sigma = singular_values(&r);
+    let condition_number = sigma[0] / sigma[sigma.len() - 1];
+
+    let lambdas = back_substitution(&r, &qtb);
+
+    let residuals: Vec<f64> = samples.iter().map(|sample| sample.y - legendre_approx_value(&lambdas, sample.x, interval)).collect();
+    let total_weight: f64 = samples.iter().map(|sample| sample.weight).sum();
+    let rms = (samples.iter().zip(residuals.iter()).map(|(sample, r)| sample.weight * r * r).sum::<f64>() / total_weight).sqrt();
+    Some(LeastSquaresFit { lambdas, residuals, rms, condition_number })
+}
+
+/// Samples f in `count` equally spaced points of the interval (both ends included)
+/// and adds normally distributed noise with standard deviation `noise` to the values.
+/// The noise comes from a linear congruential generator started at `seed`, so the same seed gives the same data.
+pub fn noisy_samples(f: &dyn TargetFunction, count: usize, noise: f64, interval: Interval, seed: u64) -> Vec<Sample> {
+    let mut state = seed;
+    // uniform in (0, 1], MMIX constants
+    let mut uniform = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((state >> 11) as f64 + 1.) / (1u64 << 53) as f64
+    };
+    (0..count)
+        .map(|i| {
+            let x = match count {
+                1 => 0.5 * (interval.a + interval.b),
+                _ => interval.a + interval.width() * i as f64 / (count - 1) as f64,
+            };
+            // Box-Muller transform
+            let gauss = (-2. * uniform().ln()).sqrt() * (2. * std::f64::consts::PI * uniform()).cos();
+            Sample::new(x, f.value(x) + noise * gauss)
+        })
+        .collect()
+}
+
+/// Target function through the samples, linear between neighbouring x and constant beyond the ends.
+/// Lets measured data go through every mode like a built-in function.
+pub struct SampledFunction {
+    name: String,
+    samples: Vec<Sample>,
+}
+
+impl SampledFunction {
+    /// Samples are sorted by x. Panics when there are none.
+    pub fn new(name: &str, mut samples: Vec<Sample>) -> SampledFunction {
+        assert!(!samples.is_empty(), "SampledFunction needs at least one sample");
+        samples.sort_by(|a, b| a.x.total_cmp(&b.x));
+        SampledFunction { name: name.to_string(), samples }
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// Smallest and largest x of the samples
+    pub fn range(&self) -> Interval {
+        Interval::new(self.samples[0].x, self.samples[self.samples.len() - 1].x)
+    }
+
+    /// Index i of the segment [x_{i-1}, x_i] containing x, 0 or len below or above the samples
+    fn segment(&self, x: f64) -> usize {
+        self.samples.partition_point(|sample| sample.x < x)
+    }
+}
+
+impl TargetFunction for SampledFunction {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn value(&self, x: f64) -> f64 {
+        match self.segment(x) {
+            0 => self.samples[0].y,
+            i if i == self.samples.len() => self.samples[i - 1].y,
+            i => {
+                let (left, right) = (self.samples[i - 1], self.samples[i]);
+                left.y + (right.y - left.y) * (x - left.x) / (right.x - left.x)
+            },
+        }
+    }
+
+    fn derivative(&self, x: f64) -> Option<f64> {
+        match self.segment(x) {
+            0 => Some(0.),
+            i if i == self.samples.len() => Some(0.),
+            i => {
+                let (left, right) = (self.samples[i - 1], self.samples[i]);
+                Some((right.y - left.y) / (right.x - left.x))
+            },
+        }
+    }
+
+    /// The interpolant has a kink in every sample
+    fn breakpoints(&self) -> Vec<f64> {
+        self.samples.iter().map(|sample| sample.x).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{functions::horner, legendre::get_coefficients};
+
+    #[test]
+    fn recovers_polynomial_from_exact_samples() {
+        // 0.5x^3 - 2x + 1
+        let p = [0.5, 0., -2., 1.];
+        let interval = Interval::new(-1., 3.);
+        let samples: Vec<Sample> = (0..12)
+            .map(|i| {
+                let x = -1.5 + 0.4 * i as f64;
+                Sample { x, y: horner(&p, x), weight: 1. + (i % 3) as f64 }
+            })
+            .collect();
+        for poly_deg in [3, 5] {
+            let fit = fit_legendre(&samples, poly_deg, interval).unwrap();
+            let coefficients = get_coefficients(&fit.lambdas, interval);
+            let expected: Vec<f64> = std::iter::repeat_n(0., poly_deg - 3).chain(p).collect();
+            for (c, e) in coefficients.iter().zip(expected.iter()) {
+                assert!((c - e).abs() < 1e-10, "{:?}", coefficients);
+            }
+            assert!(fit.rms < 1e-12);
+            assert!(fit.residuals.iter().all(|r| r.abs() < 1e-12));
+            assert!(fit.condition_number >= 1. && fit.condition_number.is_finite());
+        }
+    }
+
+    #[test]
+    fn rejects_too_few_distinct_points() {
+        let interval = Interval::new(0., 2.);
+        // six samples, but only three distinct x
+        let samples: Vec<Sample> = [0., 1., 2., 0., 1., 2.].iter().map(|x| Sample::new(*x, x * x)).collect();
+        assert!(fit_legendre(&samples, 3, interval).is_none());
+        assert!(fit_legendre(&samples[..2], 2, interval).is_none());
+
+        let fit = fit_legendre(&samples, 2, interval).unwrap();
+        assert!(fit.rms < 1e-12);
+    }
+}
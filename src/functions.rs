@@ -1,24 +1,137 @@
-use std::{
-    f64::consts::{E, PI},
-};
+use std::f64::consts::PI;
 
-use crate::Function;
+use crate::Interval;
+
+/// Function approximated by the application.
+/// Implement it and add it to a `FunctionRegistry` to make it selectable in the GUI.
+pub trait TargetFunction {
+    /// Name shown in the GUI
+    fn name(&self) -> String;
+
+    /// Value of the function in point x
+    fn value(&self, x: f64) -> f64;
+
+    /// Value of the derivative in point x, None when it isn't known
+    fn derivative(&self, _x: f64) -> Option<f64> {
+        None
+    }
+
+    /// Points where the function or its derivatives are discontinuous or singular.
+    /// Integrals over an interval containing them are split there.
+    fn breakpoints(&self) -> Vec<f64> {
+        Vec::new()
+    }
+}
+
+/// Surface f(x, y) approximated on a rectangle
+pub trait TargetFunction2D {
+    /// Name shown in the GUI
+    fn name(&self) -> String;
+
+    /// Value of the function in point (x, y)
+    fn value(&self, x: f64, y: f64) -> f64;
+}
+
+/// Target function given by plain function pointers, used for the built-in functions
+pub struct SimpleFunction {
+    pub name: &'static str,
+    pub value: fn(f64) -> f64,
+    pub derivative: Option<fn(f64) -> f64>,
+    pub breakpoints: &'static [f64],
+}
+
+impl TargetFunction for SimpleFunction {
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn value(&self, x: f64) -> f64 {
+        (self.value)(x)
+    }
+
+    fn derivative(&self, x: f64) -> Option<f64> {
+        self.derivative.map(|d| d(x))
+    }
+
+    fn breakpoints(&self) -> Vec<f64> {
+        self.breakpoints.to_vec()
+    }
+}
+
+/// List of the target functions the GUI lets the user choose from
+#[derive(Default)]
+pub struct FunctionRegistry {
+    functions: Vec<Box<dyn TargetFunction>>,
+}
+
+impl FunctionRegistry {
+    /// Empty registry
+    pub fn new() -> FunctionRegistry {
+        FunctionRegistry { functions: Vec::new() }
+    }
+
+    /// Registry with the built-in functions of this crate
+    pub fn with_builtins() -> FunctionRegistry {
+        let mut registry = FunctionRegistry::new();
+        registry.register(SimpleFunction { name: "Polynomial 2nd Power", value: polynomial1, derivative: Some(polynomial1_derivative), breakpoints: &[] });
+        registry.register(SimpleFunction { name: "Polynomial 4th power", value: polynomial2, derivative: Some(polynomial2_derivative), breakpoints: &[] });
+        registry.register(SimpleFunction { name: "Perfect Fit", value: perfect_fit, derivative: Some(perfect_fit_derivative), breakpoints: &[3.] });
+        registry.register(SimpleFunction { name: "Linear", value: linear, derivative: Some(|_| 0.5), breakpoints: &[] });
+        registry.register(SimpleFunction { name: "Sinusoidal", value: sinusoidal, derivative: Some(f64::cos), breakpoints: &[] });
+        registry.register(SimpleFunction { name: "Absolute", value: absolute, derivative: Some(f64::signum), breakpoints: &[0.] });
+        registry.register(SimpleFunction { name: "Mixed", value: mixed, derivative: Some(mixed_derivative), breakpoints: &[0., 2., 4.] });
+        registry
+    }
+
+    /// Adds a function at the end of the list
+    pub fn register(&mut self, function: impl TargetFunction + 'static) {
+        self.functions.push(Box::new(function));
+    }
+
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> &dyn TargetFunction {
+        self.functions[index].as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn TargetFunction> {
+        self.functions.iter().map(|f| f.as_ref())
+    }
+}
 
 pub fn polynomial1(x: f64) -> f64 {
     // 0.15x^2 - x - 1
     -1. + x * (-1. + x * 0.15)
 }
 
+pub fn polynomial1_derivative(x: f64) -> f64 {
+    -1. + x * 0.3
+}
+
 pub fn polynomial2(x: f64) -> f64 {
     //0.07*x^4+0.3*x^3-0.2*x^2-x-1.
     -1. + x * (-1. + x * (-0.2 + x * (-0.3 + x * 0.07)))
     //2.*E.powf(-3.*x)*(4.*x + 3.*x*x)
 }
 
+pub fn polynomial2_derivative(x: f64) -> f64 {
+    -1. + x * (-0.4 + x * (-0.9 + x * 0.28))
+}
+
 pub fn perfect_fit(x: f64) -> f64 {
     PI * (x - 3.).powi(-2) + 0.1
 }
 
+pub fn perfect_fit_derivative(x: f64) -> f64 {
+    -2. * PI * (x - 3.).powi(-3)
+}
+
 pub fn linear(x: f64) -> f64 {
     0.5 * x + 2.
 }
@@ -36,80 +149,152 @@ pub fn mixed(x: f64) -> f64 {
     ((x - 2.).abs() - 2.).abs() + x.sin() + 0.05 * x.powf(3.)
 }
 
-pub fn function_value(x: f64, func: Function) -> f64 {
-    match func {
-        Function::Poly1 => polynomial1(x),
-        Function::Poly2 => polynomial2(x),
-        Function::PerfectFit => perfect_fit(x),
-        Function::Linear => linear(x),
-        Function::Sinusoidal => sinusoidal(x),
-        Function::Absolute => absolute(x),
-        Function::Mixed => mixed(x),
+pub fn mixed_derivative(x: f64) -> f64 {
+    ((x - 2.).abs() - 2.).signum() * (x - 2.).signum() + x.cos() + 0.15 * x * x
+}
+
+/// Returns the value of a function in point x.
+/// Uses Horner's method.
+/// * a - vector of coefficients of a function for example 3x^2 + 2x + 1 = {1, 2, 3}
+pub fn horner(a: &[f64], x: f64) -> f64 {
+    let mut i = 0;
+    let mut out = a[i];
+    i += 1;
+    while i < a.len() {
+        out *= x;
+        out += a[i];
+        i += 1;
     }
+    out
 }
 
-pub fn factorial(n: usize) -> i64 {
-    if n == 0 {
-        1
-    } else {
-        n as i64 * factorial(n - 1)
+/// Substitutes t = alpha * x + beta into a polynomial given in t.
+/// Returns the coefficients of the resulting polynomial in x, highest power first like `horner` expects.
+/// * a - coefficients of the polynomial in t, highest power first
+pub fn compose_affine(a: &[f64], alpha: f64, beta: f64) -> Vec<f64> {
+    let mut out: Vec<f64> = Vec::new();
+    for coeff in a.iter() {
+        // out = out * (alpha * x + beta) + coeff
+        let mut next = vec![0.; out.len() + 1];
+        for (i, elem) in out.iter().enumerate() {
+            next[i] += elem * alpha;
+            next[i + 1] += elem * beta;
+        }
+        let last = next.len() - 1;
+        next[last] += coeff;
+        out = next;
     }
+    out
 }
 
-pub fn binomial_coeff(top: usize, bot: usize) -> i64 {
-    if top > bot {
-        factorial(top) / (factorial(top - bot) * factorial(bot))
+/// Expands sum(coeffs[k] * p_k) of a polynomial basis into monomial coefficients, highest power first.
+/// * polynomial - coefficients of p_k, highest power first
+/// * interval - the basis lives in t = (2x - a - b) / (b - a) of this interval, None when it lives in x itself
+pub fn basis_to_monomial(coeffs: &[f64], polynomial: impl Fn(usize) -> Vec<f64>, interval: Option<Interval>) -> Vec<f64> {
+    let mut out: Vec<f64> = vec![0.; coeffs.len()];
+    for (i, c) in coeffs.iter().enumerate() {
+        for (iter, elem) in polynomial(i).iter().rev().enumerate() {
+            out[coeffs.len() - 1 - iter] += elem * c;
+        }
     }
-    else if top == bot {
-        1
-    } else {
-        0
+    match interval {
+        Some(interval) => compose_affine(&out, 2. / interval.width(), -(interval.a + interval.b) / interval.width()),
+        None => out,
     }
 }
 
-pub fn pow(x: i64, n: usize) -> i64 {
-    if n == 0 {
-        return 1
+/// Returns the coefficients of the Legendre polynomial P_deg, highest power first.
+/// Built with Bonnet's recurrence, which never leaves floating point, so degrees
+/// into the hundreds don't overflow like the factorials of the explicit formula.
+/// ### (n + 1) P_{n+1}(x) = (2n + 1) x P_n(x) - n P_{n-1}(x)
+/// https://en.wikipedia.org/wiki/Legendre_polynomials#Recurrence_relations
+pub fn legendre_polynomial(deg: usize) -> Vec<f64> {
+    // coefficients lowest power first while building
+    let mut previous: Vec<f64> = Vec::new();
+    let mut current: Vec<f64> = vec![1.];
+
+    for n in 0..deg {
+        let mut next = vec![0.; n + 2];
+        for (k, elem) in current.iter().enumerate() {
+            next[k + 1] += (2 * n + 1) as f64 * elem;
+        }
+        for (k, elem) in previous.iter().enumerate() {
+            next[k] -= n as f64 * elem;
+        }
+        for elem in next.iter_mut() {
+            *elem /= (n + 1) as f64;
+        }
+        previous = current;
+        current = next;
     }
-    if n == 1 {
-        return x
+    current.reverse();
+
+    debug_assert!(check_legendre_polynomial(&current, deg));
+    current
+}
+
+/// Checks the coefficients of P_deg (highest power first) against known facts that don't come from the recurrence:
+/// ### P_n(1) = 1, P_n(-1) = (-1)^n, P_n'(1) = n (n + 1) / 2
+/// ### leading coefficient = 2^n * binomial(2n, n) / 4^n
+/// Summing the coefficients loses up to machine epsilon times the sum of their absolute values,
+/// so the values are compared with that tolerance.
+pub fn check_legendre_polynomial(coeffs: &[f64], deg: usize) -> bool {
+    if coeffs.len() != deg + 1 {
+        return false;
     }
-    let mut out = x;
-    for i in 1..n {
-        out *= x;
+    let tolerance = 4. * (deg + 1) as f64 * f64::EPSILON;
+    // power of x belonging to coeffs[i]
+    let power = |i: usize| deg - i;
+
+    let magnitude: f64 = coeffs.iter().map(|c| c.abs()).sum();
+    let value_at_one: f64 = coeffs.iter().sum();
+    if (value_at_one - 1.).abs() > tolerance * magnitude {
+        return false;
+    }
+    let value_at_minus_one: f64 = coeffs.iter().enumerate().map(|(i, c)| if power(i) % 2 == 0 { *c } else { -c }).sum();
+    let sign = (-1f64).powi(deg as i32);
+    if (value_at_minus_one - sign).abs() > tolerance * magnitude {
+        return false;
+    }
+    let slope_at_one: f64 = coeffs.iter().enumerate().map(|(i, c)| power(i) as f64 * c).sum();
+    let slope_magnitude: f64 = coeffs.iter().enumerate().map(|(i, c)| power(i) as f64 * c.abs()).sum();
+    if (slope_at_one - (deg * (deg + 1)) as f64 / 2.).abs() > tolerance * slope_magnitude.max(1.) {
+        return false;
     }
-    out
-}
 
-/// Returns the value of a function in point x.
-/// Uses Horner's method.
-/// * a - vector of coefficients of a function for example 3x^2 + 2x + 1 = {1, 2, 3}
-pub fn horner(a: &Vec<f64>, x: f64) -> f64 {
-    let mut i = 0;
-    let mut out = a[i as usize];
-    i += 1;
-    while i < a.len() {
-        out *= x;
-        out += a[i as usize];
-        i += 1;
+    // binomial(2n, n) / 4^n = prod_{k=1}^{n} (n + k) / (4k), kept near 1 / sqrt(pi n)
+    let mut central = 1.;
+    for k in 1..=deg {
+        central *= (deg + k) as f64 / (4 * k) as f64;
     }
-    out
+    let leading = central * 2f64.powi(deg as i32);
+    (coeffs[0] - leading).abs() <= tolerance * leading
 }
 
-/// https://en.wikipedia.org/wiki/Legendre_polynomials#Rodrigues'_formula_and_other_explicit_formulas
-pub fn legendre_polynomial(deg: usize) -> Vec<f64> {
-    let mut out: Vec<f64> = Vec::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for k in 0..(deg/2)+1 {
-        out.push( ((-1i64).pow(k as u32) * binomial_coeff(deg, k) * binomial_coeff(2*deg - 2*k, deg)) as f64);
-        out.push(0.);
-    }
-    if deg % 2 != 1 {
-        out.pop();
+    #[test]
+    fn legendre_polynomials_pass_the_check_into_the_hundreds() {
+        for deg in [0, 1, 2, 3, 10, 50, 100, 200, 300] {
+            assert!(check_legendre_polynomial(&legendre_polynomial(deg), deg), "P_{} failed", deg);
+        }
     }
-    for elem in out.iter_mut() {
-        *elem *= 1./2f64.powi(deg as i32);
+
+    #[test]
+    fn check_catches_wrong_coefficients() {
+        assert!(!check_legendre_polynomial(&[1., 0.], 2));
+        for deg in [2, 7, 100] {
+            let mut coeffs = legendre_polynomial(deg);
+            coeffs[0] *= 1. + 1e-9;
+            assert!(!check_legendre_polynomial(&coeffs, deg));
+        }
+        // a wrong linear term only shows in the values at +-1 and the slope
+        for deg in [2, 7] {
+            let mut coeffs = legendre_polynomial(deg);
+            coeffs[deg - 1] += 0.1;
+            assert!(!check_legendre_polynomial(&coeffs, deg));
+        }
     }
-    
-    out
-}
\ No newline at end of file
+}
@@ -0,0 +1,354 @@
+use std::f64::consts::PI;
+
+use crate::integral::Quadrature;
+
+/// n-point Gauss-Legendre quadrature, nodes and weights are computed once on construction
+pub struct GaussLegendre {
+    nodes: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl GaussLegendre {
+    pub fn new(n: usize) -> GaussLegendre {
+        let (nodes, weights) = gauss_legendre(n);
+        GaussLegendre { nodes, weights }
+    }
+}
+
+impl Quadrature for GaussLegendre {
+    fn name(&self) -> String {
+        format!("Gauss-Legendre ({} nodes)", self.nodes.len())
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        let half = 0.5 * (b - a);
+        let mid = 0.5 * (a + b);
+        let mut sum = 0.;
+        for (x, w) in self.nodes.iter().zip(self.weights.iter()) {
+            sum += w * f(mid + half * x);
+        }
+        sum * half
+    }
+}
+
+/// Nonnegative nodes of the 15-point Kronrod rule, the odd ones are the nodes of the 7-point Gauss rule
+const KRONROD_NODES: [f64; 8] = [
+    0.9914553711208126,
+    0.9491079123427585,
+    0.8648644233597691,
+    0.7415311855993945,
+    0.5860872354676911,
+    0.4058451513773972,
+    0.20778495500789848,
+    0.,
+];
+
+/// Weights of the 15-point Kronrod rule belonging to KRONROD_NODES
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022935322010529224,
+    0.06309209262997856,
+    0.10479001032225019,
+    0.14065325971552592,
+    0.1690047266392679,
+    0.19035057806478542,
+    0.20443294007529889,
+    0.20948214108472782,
+];
+
+/// Weights of the 7-point Gauss rule belonging to KRONROD_NODES[1], [3], [5] and [7]
+const GAUSS7_WEIGHTS: [f64; 4] = [
+    0.1294849661688697,
+    0.27970539148927664,
+    0.3818300505051189,
+    0.4179591836734694,
+];
+
+/// Returns the 15-point Kronrod estimate of the integral over [a, b]
+/// and the difference from the embedded 7-point Gauss rule as the error estimate.
+fn kronrod15(f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let half = 0.5 * (b - a);
+    let mid = 0.5 * (a + b);
+    let center = f(mid);
+    let mut kronrod = KRONROD_WEIGHTS[7] * center;
+    let mut gauss = GAUSS7_WEIGHTS[3] * center;
+    for i in 0..7 {
+        let pair = f(mid - half * KRONROD_NODES[i]) + f(mid + half * KRONROD_NODES[i]);
+        kronrod += KRONROD_WEIGHTS[i] * pair;
+        if i % 2 == 1 {
+            gauss += GAUSS7_WEIGHTS[i / 2] * pair;
+        }
+    }
+    (kronrod * half, ((kronrod - gauss) * half).abs())
+}
+
+/// Globally adaptive Gauss-Kronrod (G7K15) quadrature.
+/// The subinterval with the largest error estimate is bisected until the sum of estimates
+/// drops below the tolerance or relative_tolerance times the integral, or max_subdivisions is reached.
+/// https://en.wikipedia.org/wiki/Gauss%E2%80%93Kronrod_quadrature_formula
+pub struct GaussKronrod {
+    pub tolerance: f64,
+    pub relative_tolerance: f64,
+    pub max_subdivisions: usize,
+}
+
+impl Quadrature for GaussKronrod {
+    fn name(&self) -> String {
+        format!("Gauss-Kronrod G7K15 (tol {:.0e})", self.tolerance)
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        self.integrate_with_error(f, a, b).0
+    }
+
+    fn integrate_with_error(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, Option<f64>) {
+        // (a, b, value, error) of every subinterval
+        let (value, error) = kronrod15(f, a, b);
+        let mut intervals = vec![(a, b, value, error)];
+        let mut total_error = error;
+        let mut total = value;
+        while total_error > self.tolerance.max(self.relative_tolerance * total.abs()) && intervals.len() < self.max_subdivisions {
+            let mut worst = 0;
+            for (i, interval) in intervals.iter().enumerate() {
+                if interval.3 > intervals[worst].3 {
+                    worst = i;
+                }
+            }
+            let (a, b, _, _) = intervals.swap_remove(worst);
+            let m = 0.5 * (a + b);
+            let (left, left_error) = kronrod15(f, a, m);
+            let (right, right_error) = kronrod15(f, m, b);
+            intervals.push((a, m, left, left_error));
+            intervals.push((m, b, right, right_error));
+            total_error = intervals.iter().map(|i| i.3).sum();
+            total = intervals.iter().map(|i| i.2).sum();
+        }
+        (total, Some(total_error))
+    }
+}
+
+/// Tensor-product Gauss-Legendre rule with n x n nodes on the square [-1, 1] x [-1, 1].
+/// Integrates polynomials of degree up to 2n - 1 in each variable exactly.
+/// Returns (t, s, weight) triples.
+pub fn gauss_legendre_2d(n: usize) -> Vec<(f64, f64, f64)> {
+    let (x, w) = gauss_legendre(n);
+    let mut out: Vec<(f64, f64, f64)> = Vec::new();
+    for (t, wt) in x.iter().zip(w.iter()) {
+        for (s, ws) in x.iter().zip(w.iter()) {
+            out.push((*t, *s, wt * ws));
+        }
+    }
+    out
+}
+
+/// Returns the nodes and weights of the n-point Gauss-Legendre quadrature on [-1, 1].
+/// The rule integrates polynomials of degree up to 2n - 1 exactly.
+///
+/// Nodes are the roots of P_n, found with Newton's iteration started from the
+/// asymptotic approximation cos(pi * (i - 0.25) / (n + 0.5)).
+/// https://en.wikipedia.org/wiki/Gauss%E2%80%93Legendre_quadrature
+pub fn gauss_legendre(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut nodes = vec![0.; n];
+    let mut weights = vec![0.; n];
+    // roots are symmetric, so only half of them has to be found
+    for i in 0..n.div_ceil(2) {
+        let mut z = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+        let mut dp = 1.;
+        for _ in 0..100 {
+            let (p, d) = legendre_with_derivative(n, z);
+            dp = d;
+            let z_prev = z;
+            z = z_prev - p / dp;
+            if (z - z_prev).abs() <= 1e-15 {
+                break;
+            }
+        }
+        nodes[i] = -z;
+        nodes[n - 1 - i] = z;
+        weights[i] = 2. / ((1. - z * z) * dp * dp);
+        weights[n - 1 - i] = weights[i];
+    }
+    (nodes, weights)
+}
+
+/// Returns the nodes and weights of the n-point Gauss-Laguerre quadrature
+/// ### Integral of e^(-x) * g(x) from 0 to infinity = sum(weights[i] * g(nodes[i]))
+/// Nodes are the roots of L_n, found with Newton's iteration from the initial guesses
+/// of Numerical Recipes (gaulag). Weights of the last nodes underflow to zero for large n.
+/// https://en.wikipedia.org/wiki/Gauss%E2%80%93Laguerre_quadrature
+pub fn gauss_laguerre(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut nodes: Vec<f64> = vec![0.; n];
+    let mut weights: Vec<f64> = vec![0.; n];
+    let mut z = 0.;
+    for i in 0..n {
+        z = match i {
+            0 => 3. / (1. + 2.4 * n as f64),
+            1 => z + 15. / (1. + 2.5 * n as f64),
+            _ => {
+                let ai = (i - 1) as f64;
+                z + (1. + 2.55 * ai) / (1.9 * ai) * (z - nodes[i - 2])
+            },
+        };
+        let mut p_prev = 0.;
+        let mut dp = 1.;
+        for _ in 0..100 {
+            // L_n(z) and L_{n-1}(z) from the recurrence (k + 1) L_{k+1} = (2k + 1 - z) L_k - k L_{k-1}
+            let mut p1 = 1.;
+            let mut p2 = 0.;
+            for k in 0..n {
+                let p3 = p2;
+                p2 = p1;
+                p1 = ((2 * k + 1) as f64 - z) * p2 / (k + 1) as f64 - k as f64 * p3 / (k + 1) as f64;
+            }
+            p_prev = p2;
+            dp = n as f64 * (p1 - p2) / z;
+            let z_prev = z;
+            z = z_prev - p1 / dp;
+            if (z - z_prev).abs() <= 1e-14 * z.abs() {
+                break;
+            }
+        }
+        nodes[i] = z;
+        weights[i] = -1. / (dp * n as f64 * p_prev);
+    }
+    (nodes, weights)
+}
+
+/// Returns the nodes and weights of the n-point Gauss-Hermite quadrature
+/// ### Integral of e^(-x^2) * g(x) over the real line = sum(weights[i] * g(nodes[i]))
+/// Newton's iteration runs on the orthonormal Hermite functions, which don't overflow,
+/// from the initial guesses of Numerical Recipes (gauher).
+/// https://en.wikipedia.org/wiki/Gauss%E2%80%93Hermite_quadrature
+pub fn gauss_hermite(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let pim4 = PI.powf(-0.25);
+    let mut nodes: Vec<f64> = vec![0.; n];
+    let mut weights: Vec<f64> = vec![0.; n];
+    // positive roots from the largest one
+    let mut roots: Vec<f64> = Vec::new();
+    let mut z = 0.;
+    for i in 0..n.div_ceil(2) {
+        z = match i {
+            0 => (2. * n as f64 + 1.).sqrt() - 1.85575 * (2. * n as f64 + 1.).powf(-1. / 6.),
+            1 => z - 1.14 * (n as f64).powf(0.426) / z,
+            2 => 1.86 * z - 0.86 * roots[0],
+            3 => 1.91 * z - 0.91 * roots[1],
+            _ => 2. * z - roots[i - 2],
+        };
+        let mut dp = 1.;
+        for _ in 0..100 {
+            let mut p1 = pim4;
+            let mut p2 = 0.;
+            for k in 0..n {
+                let p3 = p2;
+                p2 = p1;
+                p1 = z * (2. / (k + 1) as f64).sqrt() * p2 - (k as f64 / (k + 1) as f64).sqrt() * p3;
+            }
+            dp = (2. * n as f64).sqrt() * p2;
+            let z_prev = z;
+            z = z_prev - p1 / dp;
+            if (z - z_prev).abs() <= 1e-14 {
+                break;
+            }
+        }
+        roots.push(z);
+        nodes[i] = -z;
+        nodes[n - 1 - i] = z;
+        weights[i] = 2. / (dp * dp);
+        weights[n - 1 - i] = weights[i];
+    }
+    (nodes, weights)
+}
+
+/// Golub-Welsch algorithm, returns the nodes and weights of the Gauss rule of any weight function
+/// from the recurrence of its monic orthogonal polynomials
+/// ### p_{k+1}(x) = (x - alpha_k) p_k(x) - beta_k p_{k-1}(x)
+/// The nodes are the eigenvalues of the symmetric tridiagonal (Jacobi) matrix with diagonal alpha_0..alpha_{n-1}
+/// and off-diagonal sqrt(beta_1)..sqrt(beta_{n-1}), the weights are mu0 times the squared first components
+/// of the normalized eigenvectors. The eigenvalues are found with the implicit QL algorithm (Numerical Recipes, tqli),
+/// which only has to track the first row of the eigenvector matrix.
+/// * alpha - alpha_0..alpha_{n-1}
+/// * beta - beta_1..beta_{n-1}
+/// * mu0 - integral of the weight function
+///
+/// https://en.wikipedia.org/wiki/Gaussian_quadrature#The_Golub-Welsch_algorithm
+pub fn golub_welsch(alpha: &[f64], beta: &[f64], mu0: f64) -> (Vec<f64>, Vec<f64>) {
+    let n = alpha.len();
+    let mut d: Vec<f64> = alpha.to_vec();
+    // e[i] couples d[i] and d[i + 1]
+    let mut e: Vec<f64> = beta.iter().map(|b| b.sqrt()).collect();
+    e.resize(n, 0.);
+    let mut z: Vec<f64> = vec![0.; n];
+    if n == 0 {
+        return (d, z);
+    }
+    z[0] = 1.;
+
+    for l in 0..n {
+        for _ in 0..60 {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs() <= f64::EPSILON * dd {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+            let mut g = (d[l + 1] - d[l]) / (2. * e[l]);
+            let mut r = g.hypot(1.);
+            g = d[m] - d[l] + e[l] / (g + r.copysign(g));
+            let mut s = 1.;
+            let mut c = 1.;
+            let mut p = 0.;
+            let mut underflow = false;
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = f.hypot(g);
+                e[i + 1] = r;
+                if r == 0. {
+                    d[i + 1] -= p;
+                    e[m] = 0.;
+                    underflow = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + 2. * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+                let f = z[i + 1];
+                z[i + 1] = s * z[i] + c * f;
+                z[i] = c * z[i] - s * f;
+            }
+            if underflow {
+                continue;
+            }
+            d[l] -= p;
+            e[l] = g;
+            e[m] = 0.;
+        }
+    }
+
+    let mut rule: Vec<(f64, f64)> = d.into_iter().zip(z.iter().map(|v| mu0 * v * v)).collect();
+    rule.sort_by(|a, b| a.0.total_cmp(&b.0));
+    rule.into_iter().unzip()
+}
+
+/// Returns P_n(x) and P_n'(x) computed with the three-term recurrence
+/// ### (k + 1) P_{k+1}(x) = (2k + 1) x P_k(x) - k P_{k-1}(x)
+fn legendre_with_derivative(n: usize, x: f64) -> (f64, f64) {
+    let mut p0 = 1.;
+    let mut p1 = 0.;
+    for k in 0..n {
+        let p2 = p1;
+        p1 = p0;
+        p0 = ((2 * k + 1) as f64 * x * p1 - k as f64 * p2) / (k + 1) as f64;
+    }
+    // p0 = P_n, p1 = P_{n-1}
+    let dp = n as f64 * (x * p0 - p1) / (x * x - 1.);
+    (p0, dp)
+}
@@ -0,0 +1,109 @@
+use std::f64::consts::{PI, SQRT_2};
+
+use crate::{functions::{basis_to_monomial, TargetFunction}, gauss::gauss_hermite};
+
+/// Variant of the Hermite polynomials
+/// * Physicists - H_n, orthogonal with weight e^(-x^2), H_{k+1} = 2x H_k - 2k H_{k-1}
+/// * Probabilists - He_n, orthogonal with weight e^(-x^2 / 2), He_{k+1} = x He_k - k He_{k-1}
+///
+/// https://en.wikipedia.org/wiki/Hermite_polynomials
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HermiteKind {
+    Physicists,
+    Probabilists,
+}
+
+impl HermiteKind {
+    /// Factor s of the recurrence P_{k+1} = s * (x P_k - k P_{k-1})
+    fn scale(&self) -> f64 {
+        match self {
+            HermiteKind::Physicists => 2.,
+            HermiteKind::Probabilists => 1.,
+        }
+    }
+
+    /// Integral of P_k^2 times the weight over the real line
+    pub fn norm(&self, k: usize) -> f64 {
+        let factorial: f64 = (1..=k).map(|i| i as f64).product();
+        match self {
+            HermiteKind::Physicists => PI.sqrt() * 2f64.powi(k as i32) * factorial,
+            HermiteKind::Probabilists => (2. * PI).sqrt() * factorial,
+        }
+    }
+}
+
+/// Returns the coefficients of the Hermite polynomial of degree deg, highest power first
+pub fn hermite_polynomial(deg: usize, kind: HermiteKind) -> Vec<f64> {
+    let s = kind.scale();
+    // lowest power first while building
+    let mut previous: Vec<f64> = Vec::new();
+    let mut current: Vec<f64> = vec![1.];
+    for k in 0..deg {
+        let mut next = vec![0.; k + 2];
+        for (i, elem) in current.iter().enumerate() {
+            next[i + 1] += s * elem;
+        }
+        for (i, elem) in previous.iter().enumerate() {
+            next[i] -= s * k as f64 * elem;
+        }
+        previous = current;
+        current = next;
+    }
+    current.reverse();
+    current
+}
+
+/// Returns the value of the Hermite polynomial of degree deg in x, computed with the recurrence
+pub fn hermite_value(deg: usize, x: f64, kind: HermiteKind) -> f64 {
+    let s = kind.scale();
+    let mut p1 = 1.;
+    let mut p2 = 0.;
+    for k in 0..deg {
+        let p3 = p2;
+        p2 = p1;
+        p1 = s * (x * p2 - k as f64 * p3);
+    }
+    p1
+}
+
+/// calculates the coefficients of the projection of f onto the Hermite polynomials of degree 0..=deg
+/// ### c_k = integral of w(x) * f(x) * P_k(x) over the real line / norm(k)
+/// The integrals use the Gauss-Hermite quadrature with `nodes` nodes,
+/// for the probabilists' weight e^(-x^2 / 2) after the substitution x = sqrt(2) * u.
+pub fn hermite_coefficients(f: &dyn TargetFunction, deg: usize, nodes: usize, kind: HermiteKind) -> Vec<f64> {
+    let (u, w) = gauss_hermite(nodes);
+    let (scale, jacobian) = match kind {
+        HermiteKind::Physicists => (1., 1.),
+        HermiteKind::Probabilists => (SQRT_2, SQRT_2),
+    };
+    let x: Vec<f64> = u.iter().map(|u| scale * u).collect();
+    let values: Vec<f64> = x.iter().map(|x| f.value(*x)).collect();
+    (0..(deg+1))
+    .map(|k| {
+        let mut sum = 0.;
+        for i in 0..x.len() {
+            sum += w[i] * values[i] * hermite_value(k, x[i], kind);
+        }
+        sum * jacobian / kind.norm(k)
+    })
+    .collect()
+}
+
+/// Returns the value of the Hermite series sum(c_k * P_k(x)) using Clenshaw's recurrence
+/// ### b_k = c_k + s * x * b_{k+1} - s * (k + 1) * b_{k+2}, value = b_0
+pub fn hermite_approx_value(coeffs: &[f64], x: f64, kind: HermiteKind) -> f64 {
+    let s = kind.scale();
+    let mut b1 = 0.;
+    let mut b2 = 0.;
+    for k in (0..coeffs.len()).rev() {
+        let b0 = coeffs[k] + s * x * b1 - s * (k + 1) as f64 * b2;
+        b2 = b1;
+        b1 = b0;
+    }
+    b1
+}
+
+/// Returns the coefficients of the Hermite approximation in x, highest power first
+pub fn get_hermite_coefficients(coeffs: &[f64], kind: HermiteKind) -> Vec<f64> {
+    basis_to_monomial(coeffs, |k| hermite_polynomial(k, kind), None)
+}
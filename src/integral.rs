@@ -1,61 +1,354 @@
-use crate::{Function, functions::{function_value, horner}};
+use std::f64::consts::PI;
 
+use crate::gauss::{GaussKronrod, GaussLegendre};
+
+/// Numerical integration rule used for the lambda coefficients
+pub trait Quadrature {
+    /// Name shown in the GUI
+    fn name(&self) -> String;
+
+    /// Returns the approximate value of the integral of f from a to b
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64;
+
+    /// Returns the value of the integral together with an estimate of its absolute error.
+    /// Rules with a fixed number of nodes don't estimate their error and return None.
+    fn integrate_with_error(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, Option<f64>) {
+        (self.integrate(f, a, b), None)
+    }
+}
+
+/// Integrates f from a to b separately between the breakpoints lying inside (a, b),
+/// so kinks and jumps of f fall onto the ends of the pieces.
+/// The error estimates of the pieces are summed, None if any piece has none.
+pub fn integrate_split(quadrature: &dyn Quadrature, f: &dyn Fn(f64) -> f64, a: f64, b: f64, breakpoints: &[f64]) -> (f64, Option<f64>) {
+    let mut points: Vec<f64> = breakpoints.iter().copied().filter(|x| *x > a && *x < b).collect();
+    points.sort_by(|x, y| x.total_cmp(y));
+    points.insert(0, a);
+    points.push(b);
+
+    let mut value = 0.;
+    let mut error = Some(0.);
+    for piece in points.windows(2) {
+        let (piece_value, piece_error) = quadrature.integrate_with_error(f, piece[0], piece[1]);
+        value += piece_value;
+        error = match (error, piece_error) {
+            (Some(error), Some(piece_error)) => Some(error + piece_error),
+            _ => None,
+        };
+    }
+    (value, error)
+}
+
+/// Quadrature rules selectable in the GUI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuadratureKind {
+    Trapezoid,
+    Simpson,
+    NewtonCotes,
+    Romberg,
+    ClenshawCurtis,
+    GaussLegendre,
+    AdaptiveSimpson,
+    GaussKronrod,
+}
+
+impl QuadratureKind {
+    pub const ALL: [QuadratureKind; 8] = [
+        QuadratureKind::Trapezoid,
+        QuadratureKind::Simpson,
+        QuadratureKind::NewtonCotes,
+        QuadratureKind::Romberg,
+        QuadratureKind::ClenshawCurtis,
+        QuadratureKind::GaussLegendre,
+        QuadratureKind::AdaptiveSimpson,
+        QuadratureKind::GaussKronrod,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuadratureKind::Trapezoid => "Trapezoid",
+            QuadratureKind::Simpson => "Simpson",
+            QuadratureKind::NewtonCotes => "Newton-Cotes",
+            QuadratureKind::Romberg => "Romberg",
+            QuadratureKind::ClenshawCurtis => "Clenshaw-Curtis",
+            QuadratureKind::GaussLegendre => "Gauss-Legendre",
+            QuadratureKind::AdaptiveSimpson => "Adaptive Simpson",
+            QuadratureKind::GaussKronrod => "Gauss-Kronrod (G7K15)",
+        }
+    }
+
+    /// Adaptive rules refine until a tolerance instead of using a fixed amount of nodes
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, QuadratureKind::AdaptiveSimpson | QuadratureKind::GaussKronrod)
+    }
+}
+
+/// Builds the chosen quadrature rule.
+/// * nodes - amount of panels for the composite rules, nodes for Gauss and Clenshaw-Curtis,
+///   Romberg uses as many levels as needed to reach that many panels
+/// * order - order of the closed Newton-Cotes formula, only used by QuadratureKind::NewtonCotes
+/// * tolerance - requested absolute error of the adaptive rules
+pub fn make_quadrature(kind: QuadratureKind, nodes: usize, order: usize, tolerance: f64) -> Box<dyn Quadrature> {
+    match kind {
+        QuadratureKind::Trapezoid => Box::new(Trapezoid { panels: nodes }),
+        QuadratureKind::Simpson => Box::new(Simpson { panels: nodes }),
+        QuadratureKind::NewtonCotes => Box::new(NewtonCotes { order, panels: nodes }),
+        QuadratureKind::Romberg => Box::new(Romberg { levels: (nodes as f64).log2().ceil() as usize + 1 }),
+        QuadratureKind::ClenshawCurtis => Box::new(ClenshawCurtis::new(nodes)),
+        QuadratureKind::GaussLegendre => Box::new(GaussLegendre::new(nodes)),
+        QuadratureKind::AdaptiveSimpson => Box::new(AdaptiveSimpson { tolerance, max_depth: 50, max_evaluations: 100_000 }),
+        QuadratureKind::GaussKronrod => Box::new(GaussKronrod { tolerance, relative_tolerance: 0., max_subdivisions: 1000 }),
+    }
+}
+
+/// Composite trapezoid rule with equal panels
+pub struct Trapezoid {
+    pub panels: usize,
+}
+
+impl Quadrature for Trapezoid {
+    fn name(&self) -> String {
+        format!("Trapezoid ({} panels)", self.panels)
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        let h = (b - a) / self.panels as f64;
+        let mut sum = 0.5 * (f(a) + f(b));
+        for i in 1..self.panels {
+            sum += f(a + i as f64 * h);
+        }
+        sum * h
+    }
+}
 
 // IMPLEMENTATION OF NEWTON COTES INTEGRATION FROM EXCERCISE 4.1
 
-/// Calculates the numerator of the lambda coefficients
-/// ### Integral of f(x) * L_k(x) from a to b
-pub fn newton_cotes_top(f: Function, poly: Vec<f64>, a: f64, b: f64) -> f64 {
-    let h = (b - a) / 2. as f64;
-    let mut sum = 0.;
-    sum += function_value(a, f) * horner(&poly, a);
-    sum += 4. * function_value(a + h, f) * horner(&poly, a + h);
-    sum += function_value(b, f) * horner(&poly, b);
-    sum * h / 3.
-}
-
-/// Calculates the denominator of the lambda coefficients
-/// ### Integral of L_k(x) * L_k(x) from a to b
-pub fn newton_cotes_bot(_f: Function, poly: Vec<f64>, a: f64, b: f64) -> f64 {
-    let h = (b - a) / 2. as f64;
-    let mut sum = 0.;
-    sum += horner(&poly, a) * horner(&poly, a);
-    sum += 4. * horner(&poly, a+h) * horner(&poly, a+h);
-    sum += horner(&poly, b) * horner(&poly, b);
-
-    sum * h / 3.
-}
-
-/// Returns the value of the Newton-Cotes integration formula for the given function with given precision.
-/// * f - chosen function from the Function enum
-/// * poly - polynomial coefficient to be calculated using Horner's method
-/// * nodes - amount of nodes for the Newton-Cotes integration
-/// * which - changes between numerator and denominator of a lambda coefficient
-pub fn newton_cotes(f: Function, poly: Vec<f64>, nodes: usize, which: bool) -> f64 
-{
-    let a = -1.;
-    let b = 1.;
-
-    match which {
-        true => {
-            let h = (b - a) / (nodes as f64);
+/// Composite Simpson rule, every panel is integrated with the 3 point formula
+pub struct Simpson {
+    pub panels: usize,
+}
+
+impl Quadrature for Simpson {
+    fn name(&self) -> String {
+        format!("Simpson ({} panels)", self.panels)
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        let width = (b - a) / self.panels as f64;
+        let h = width / 2.;
+        let mut sum = 0.;
+        let mut x = a;
+        for _ in 0..self.panels {
+            sum += f(x) + 4. * f(x + h) + f(x + width);
+            x += width;
+        }
+        sum * h / 3.
+    }
+}
+
+/// Weights of the closed Newton-Cotes formulas of order 1 to 8 and their common factor.
+/// The formula of order n uses n + 1 equally spaced points with spacing h
+/// ### Integral = factor * h * sum(weights[i] * f(x_i))
+const NEWTON_COTES_WEIGHTS: [(f64, &[f64]); 8] = [
+    (1. / 2., &[1., 1.]),
+    (1. / 3., &[1., 4., 1.]),
+    (3. / 8., &[1., 3., 3., 1.]),
+    (2. / 45., &[7., 32., 12., 32., 7.]),
+    (5. / 288., &[19., 75., 50., 50., 75., 19.]),
+    (1. / 140., &[41., 216., 27., 272., 27., 216., 41.]),
+    (7. / 17280., &[751., 3577., 1323., 2989., 2989., 1323., 3577., 751.]),
+    (4. / 14175., &[989., 5888., -928., 10496., -4540., 10496., -928., 5888., 989.]),
+];
+
+/// Composite closed Newton-Cotes formula of order 1 to 8
+/// (order 1 is the trapezoid rule, order 2 is Simpson's rule)
+pub struct NewtonCotes {
+    pub order: usize,
+    pub panels: usize,
+}
+
+impl Quadrature for NewtonCotes {
+    fn name(&self) -> String {
+        format!("Newton-Cotes order {} ({} panels)", self.order, self.panels)
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        let order = self.order.clamp(1, NEWTON_COTES_WEIGHTS.len());
+        let (factor, weights) = NEWTON_COTES_WEIGHTS[order - 1];
+        let width = (b - a) / self.panels as f64;
+        let h = width / order as f64;
+        let mut sum = 0.;
+        for panel in 0..self.panels {
+            let x = a + panel as f64 * width;
+            for (i, w) in weights.iter().enumerate() {
+                sum += w * f(x + i as f64 * h);
+            }
+        }
+        sum * factor * h
+    }
+}
+
+/// Romberg integration, Richardson extrapolation of the trapezoid rule
+/// with 1, 2, 4, ..., 2^(levels - 1) panels
+pub struct Romberg {
+    pub levels: usize,
+}
+
+impl Quadrature for Romberg {
+    fn name(&self) -> String {
+        format!("Romberg ({} levels)", self.levels)
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        let levels = self.levels.max(1);
+        let mut h = b - a;
+        let mut previous: Vec<f64> = vec![0.5 * h * (f(a) + f(b))];
+        for level in 1..levels {
+            h /= 2.;
+            // trapezoid rule with 2^level panels reuses the previous one
             let mut sum = 0.;
-            let mut x = a;
-            for i in 0..nodes {
-                sum += newton_cotes_top(f, poly.to_vec(), x, x + h);
-                x += h;
+            for i in 0..(1usize << (level - 1)) {
+                sum += f(a + (2 * i + 1) as f64 * h);
+            }
+            let mut current: Vec<f64> = vec![0.5 * previous[0] + h * sum];
+            let mut power = 1.;
+            for j in 1..=level {
+                power *= 4.;
+                current.push(current[j - 1] + (current[j - 1] - previous[j - 1]) / (power - 1.));
             }
-            return sum
-        },
-        false => {
-            let h = (b - a) / (nodes as f64);
+            previous = current;
+        }
+        previous[previous.len() - 1]
+    }
+}
+
+/// Clenshaw-Curtis quadrature with n + 1 nodes x_j = cos(j * pi / n)
+/// https://en.wikipedia.org/wiki/Clenshaw%E2%80%93Curtis_quadrature
+pub struct ClenshawCurtis {
+    nodes: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl ClenshawCurtis {
+    pub fn new(n: usize) -> ClenshawCurtis {
+        let n = n.max(1);
+        let mut nodes = Vec::new();
+        let mut weights = Vec::new();
+        for j in 0..=n {
+            let theta = j as f64 * PI / n as f64;
             let mut sum = 0.;
-            let mut x = a;
-            for i in 0..nodes {
-                sum += newton_cotes_bot(f, poly.to_vec(), x, x + h);
-                x += h;
+            for k in 1..=n / 2 {
+                let b = if 2 * k == n { 1. } else { 2. };
+                sum += b / (4. * (k * k) as f64 - 1.) * (2. * k as f64 * theta).cos();
             }
-            return sum
-        },
-    };  
-}
\ No newline at end of file
+            let c = if j == 0 || j == n { 1. } else { 2. };
+            nodes.push(theta.cos());
+            weights.push(c / n as f64 * (1. - sum));
+        }
+        ClenshawCurtis { nodes, weights }
+    }
+}
+
+impl Quadrature for ClenshawCurtis {
+    fn name(&self) -> String {
+        format!("Clenshaw-Curtis ({} nodes)", self.nodes.len())
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        let half = 0.5 * (b - a);
+        let mid = 0.5 * (a + b);
+        let mut sum = 0.;
+        for (x, w) in self.nodes.iter().zip(self.weights.iter()) {
+            sum += w * f(mid + half * x);
+        }
+        sum * half
+    }
+}
+
+/// Adaptive Simpson's rule, halves each panel until the Richardson error estimate
+/// |S(left) + S(right) - S(whole)| / 15 meets its share of the tolerance.
+/// Panels where f isn't finite aren't halved further, and the halving stops once max_evaluations are used up
+/// (the halves still pending then add at most 2 * max_depth evaluations).
+/// https://en.wikipedia.org/wiki/Adaptive_Simpson%27s_method
+pub struct AdaptiveSimpson {
+    pub tolerance: f64,
+    pub max_depth: usize,
+    pub max_evaluations: usize,
+}
+
+impl AdaptiveSimpson {
+    /// Returns the integral over [a, b] and its error estimate
+    /// * fa, fm, fb - values of f in a, the midpoint and b
+    /// * whole - Simpson's rule over the whole [a, b]
+    /// * evaluations - evaluations of f so far
+    #[allow(clippy::too_many_arguments)]
+    fn recurse(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64, fa: f64, fm: f64, fb: f64, whole: f64, tolerance: f64, depth: usize, evaluations: &mut usize) -> (f64, f64) {
+        let m = 0.5 * (a + b);
+        let lm = 0.5 * (a + m);
+        let rm = 0.5 * (m + b);
+        let flm = f(lm);
+        let frm = f(rm);
+        *evaluations += 2;
+        let left = (m - a) / 6. * (fa + 4. * flm + fm);
+        let right = (b - m) / 6. * (fm + 4. * frm + fb);
+        let delta = left + right - whole;
+        if depth >= self.max_depth || *evaluations >= self.max_evaluations || !delta.is_finite() || delta.abs() <= 15. * tolerance {
+            return (left + right + delta / 15., delta.abs() / 15.);
+        }
+        let (left, left_error) = self.recurse(f, a, m, fa, flm, fm, left, tolerance / 2., depth + 1, evaluations);
+        let (right, right_error) = self.recurse(f, m, b, fm, frm, fb, right, tolerance / 2., depth + 1, evaluations);
+        (left + right, left_error + right_error)
+    }
+}
+
+impl Quadrature for AdaptiveSimpson {
+    fn name(&self) -> String {
+        format!("Adaptive Simpson (tol {:.0e})", self.tolerance)
+    }
+
+    fn integrate(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+        self.integrate_with_error(f, a, b).0
+    }
+
+    fn integrate_with_error(&self, f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, Option<f64>) {
+        let fa = f(a);
+        let fm = f(0.5 * (a + b));
+        let fb = f(b);
+        let whole = (b - a) / 6. * (fa + 4. * fm + fb);
+        let mut evaluations = 3;
+        let (value, error) = self.recurse(f, a, b, fa, fm, fb, whole, self.tolerance, 0, &mut evaluations);
+        (value, Some(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_simpson_meets_its_tolerance() {
+        let quadrature = AdaptiveSimpson { tolerance: 1e-10, max_depth: 50, max_evaluations: 100_000 };
+        let (value, error) = quadrature.integrate_with_error(&f64::exp, -1., 2.);
+        assert!((value - (2f64.exp() - (-1f64).exp())).abs() < 1e-9);
+        assert!(error.unwrap() < 1e-9);
+    }
+
+    #[test]
+    fn adaptive_simpson_stops_where_f_is_not_finite() {
+        let evaluations = std::cell::Cell::new(0);
+        let counted = |x: f64| {
+            evaluations.set(evaluations.get() + 1);
+            x.sqrt()
+        };
+        let quadrature = AdaptiveSimpson { tolerance: 1e-12, max_depth: 50, max_evaluations: 100_000 };
+        assert!(quadrature.integrate(&counted, -1., 1.).is_nan());
+        assert!(evaluations.get() < 100);
+
+        // f is finite, but the tolerance can't be met before the budget runs out
+        evaluations.set(0);
+        let quadrature = AdaptiveSimpson { tolerance: 1e-300, max_depth: 50, max_evaluations: 1000 };
+        quadrature.integrate(&|x: f64| counted(x.abs()), -1., 1.);
+        // the halves still pending when the budget runs out evaluate their midpoints once more
+        assert!(evaluations.get() <= 1000 + 2 * 50 + 2);
+    }
+}
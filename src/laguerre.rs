@@ -0,0 +1,80 @@
+use crate::{functions::{basis_to_monomial, TargetFunction}, gauss::gauss_laguerre};
+
+/// Returns the coefficients of the Laguerre polynomial L_deg, highest power first
+/// ### (k + 1) L_{k+1}(x) = (2k + 1 - x) L_k(x) - k L_{k-1}(x)
+/// https://en.wikipedia.org/wiki/Laguerre_polynomials
+pub fn laguerre_polynomial(deg: usize) -> Vec<f64> {
+    // lowest power first while building
+    let mut previous: Vec<f64> = Vec::new();
+    let mut current: Vec<f64> = vec![1.];
+    for k in 0..deg {
+        let mut next = vec![0.; k + 2];
+        for (i, elem) in current.iter().enumerate() {
+            next[i] += (2 * k + 1) as f64 * elem;
+            next[i + 1] -= elem;
+        }
+        for (i, elem) in previous.iter().enumerate() {
+            next[i] -= k as f64 * elem;
+        }
+        for elem in next.iter_mut() {
+            *elem /= (k + 1) as f64;
+        }
+        previous = current;
+        current = next;
+    }
+    current.reverse();
+    current
+}
+
+/// Returns L_deg(x) computed with the three-term recurrence
+pub fn laguerre_value(deg: usize, x: f64) -> f64 {
+    let mut p1 = 1.;
+    let mut p2 = 0.;
+    for k in 0..deg {
+        let p3 = p2;
+        p2 = p1;
+        p1 = ((2 * k + 1) as f64 - x) * p2 / (k + 1) as f64 - k as f64 * p3 / (k + 1) as f64;
+    }
+    p1
+}
+
+/// calculates the coefficients of the projection of f onto L_0..L_deg in the space with weight e^(-x) on [0, inf)
+/// ### c_k = integral of e^(-x) * f(x) * L_k(x) from 0 to infinity
+/// The Laguerre polynomials are orthonormal with this weight, so no division by the norm is needed.
+/// The integrals use the Gauss-Laguerre quadrature with `nodes` nodes.
+pub fn laguerre_coefficients(f: &dyn TargetFunction, deg: usize, nodes: usize) -> Vec<f64> {
+    let (x, w) = gauss_laguerre(nodes);
+    let values: Vec<f64> = x.iter().map(|x| f.value(*x)).collect();
+    (0..(deg+1))
+    .map(|k| {
+        let mut sum = 0.;
+        for i in 0..x.len() {
+            // weights underflowing to zero would turn an overflowing f into NaN
+            if w[i] > 0. {
+                sum += w[i] * values[i] * laguerre_value(k, x[i]);
+            }
+        }
+        sum
+    })
+    .collect()
+}
+
+/// Returns the value of the Laguerre series sum(c_k * L_k(x)) using Clenshaw's recurrence
+/// ### b_k = c_k + (2k + 1 - x) / (k + 1) * b_{k+1} - (k + 1) / (k + 2) * b_{k+2}, value = b_0
+pub fn laguerre_approx_value(coeffs: &[f64], x: f64) -> f64 {
+    let mut b1 = 0.;
+    let mut b2 = 0.;
+    for k in (0..coeffs.len()).rev() {
+        let b0 = coeffs[k]
+            + ((2 * k + 1) as f64 - x) / (k + 1) as f64 * b1
+            - (k + 1) as f64 / (k + 2) as f64 * b2;
+        b2 = b1;
+        b1 = b0;
+    }
+    b1
+}
+
+/// Returns the coefficients of the Laguerre approximation in x, highest power first
+pub fn get_laguerre_coefficients(coeffs: &[f64]) -> Vec<f64> {
+    basis_to_monomial(coeffs, laguerre_polynomial, None)
+}
@@ -1,76 +1,589 @@
-use std::error::Error;
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
-use crate::{Function, functions::{legendre_polynomial, horner, function_value}, integral::newton_cotes};
+use crate::{Interval, error_analysis::{error_norms, norm_quadrature, ErrorNorm, ErrorNorms}, functions::{legendre_polynomial, basis_to_monomial, TargetFunction}, gauss::gauss_legendre, integral::{integrate_split, Quadrature}};
 
 /// calculates lambdas for the approximation polynomial
-/// * f - Function from the Function enum
+/// * f - the approximated function
 /// * poly_deg - Degree of the approximating polynomial
-/// * integral_nodes - Amount of nodes for the Newton-Cotes integral
-pub fn calculate_lambdas(f: Function, poly_deg: usize, integral_nodes: usize) -> Vec<f64> {
-    let mut out: Vec<f64> = Vec::new();
-    for i in 0..(poly_deg+1) {
-        let poly = legendre_polynomial(i);
-        out.push(
-            newton_cotes(f, poly.to_vec(), integral_nodes , true) /
-            newton_cotes(f, poly.to_vec(), integral_nodes , false) 
-        )
+/// * interval - Interval [a, b] the approximation takes place on
+/// * quadrature - Integration rule used for the lambdas, the integrals are taken over [-1, 1]
+/// * analytic_norm - divide by the exact norm of L_k instead of integrating it numerically
+pub fn calculate_lambdas(f: &dyn TargetFunction, poly_deg: usize, interval: Interval, quadrature: &dyn Quadrature, analytic_norm: bool) -> Vec<f64> {
+    calculate_lambdas_with_errors(f, poly_deg, interval, quadrature, analytic_norm).0
+}
+
+/// calculates lambdas like `calculate_lambdas` together with the estimated error of every lambda.
+/// The errors are only known when the quadrature estimates its own error, otherwise they are None.
+/// The error of the numerator and the denominator is propagated through the quotient.
+/// The numerator is integrated piecewise between the breakpoints of f.
+pub fn calculate_lambdas_with_errors(f: &dyn TargetFunction, poly_deg: usize, interval: Interval, quadrature: &dyn Quadrature, analytic_norm: bool) -> (Vec<f64>, Vec<Option<f64>>) {
+    let breakpoints: Vec<f64> = f.breakpoints().iter().map(|x| interval.to_unit(*x)).collect();
+    (0..(poly_deg+1)).map(|k| lambda_with_error(f, k, interval, quadrature, analytic_norm, &breakpoints)).unzip()
+}
+
+/// k-th lambda and its error estimate, see `calculate_lambdas_with_errors`
+/// * breakpoints - breakpoints of f mapped onto [-1, 1]
+fn lambda_with_error(f: &dyn TargetFunction, k: usize, interval: Interval, quadrature: &dyn Quadrature, analytic_norm: bool, breakpoints: &[f64]) -> (f64, Option<f64>) {
+    let (top, top_error) = integrate_split(quadrature, &|t| f.value(interval.from_unit(t)) * legendre_value(k, t), -1., 1., breakpoints);
+    let (bot, bot_error) = match analytic_norm {
+        true => (legendre_norm(k), Some(0.)),
+        false => quadrature.integrate_with_error(&|t| legendre_value(k, t).powi(2), -1., 1.),
+    };
+    let error = match (top_error, bot_error) {
+        (Some(top_error), Some(bot_error)) => Some(top_error / bot.abs() + top.abs() * bot_error / (bot * bot)),
+        _ => None,
+    };
+    (top / bot, error)
+}
+
+/// Returns the exact norm of the Legendre polynomial
+/// ### Integral of L_k(t) * L_k(t) from -1 to 1 = 2 / (2k + 1)
+pub fn legendre_norm(k: usize) -> f64 {
+    2. / (2 * k + 1) as f64
+}
+
+/// Integrates L_k(t)^2 numerically for k = 0..=poly_deg and pairs the results with the exact norms.
+/// Every lambda computed with the numeric denominator is off by the factor analytic / numeric,
+/// so the relative discrepancy shows how much quadrature error leaks into it.
+/// Returns (numeric, analytic) pairs.
+pub fn norm_discrepancies(poly_deg: usize, quadrature: &dyn Quadrature) -> Vec<(f64, f64)> {
+    (0..(poly_deg+1))
+    .map(|k| (quadrature.integrate(&|t| legendre_value(k, t).powi(2), -1., 1.), legendre_norm(k)))
+    .collect()
+}
+
+/// Returns the value of the Legendre series sum(lambdas[k] * L_k(t)) in t from [-1, 1].
+/// Uses Clenshaw's recurrence, O(n) and without going through the monomial coefficients.
+/// ### b_k = lambda_k + (2k + 1) / (k + 1) * t * b_{k+1} - (k + 1) / (k + 2) * b_{k+2}, value = b_0
+/// https://en.wikipedia.org/wiki/Clenshaw_algorithm
+pub fn clenshaw(lambdas: &[f64], t: f64) -> f64 {
+    let mut b1 = 0.;
+    let mut b2 = 0.;
+    for k in (0..lambdas.len()).rev() {
+        let b0 = lambdas[k]
+            + (2 * k + 1) as f64 / (k + 1) as f64 * t * b1
+            - (k + 1) as f64 / (k + 2) as f64 * b2;
+        b2 = b1;
+        b1 = b0;
+    }
+    b1
+}
+
+/// Returns L_0(t)..L_deg(t) computed with Bonnet's recurrence
+pub fn legendre_values(deg: usize, t: f64) -> Vec<f64> {
+    let mut out = vec![1.];
+    if deg > 0 {
+        out.push(t);
+    }
+    for k in 1..deg {
+        let next = ((2 * k + 1) as f64 * t * out[k] - k as f64 * out[k - 1]) / (k + 1) as f64;
+        out.push(next);
     }
     out
 }
 
-pub fn legendre_approx_value(lambdas: &Vec<f64> , x: f64) -> f64 {
-    let mut sum = 0.;
-    for (i, lambda) in lambdas.iter().enumerate() {
-        sum += lambda * horner(&legendre_polynomial(i), x);
+/// Returns L_k(t) computed with Bonnet's recurrence, which stays accurate at high degrees
+/// where the monomial coefficients of L_k cancel each other
+pub fn legendre_value(k: usize, t: f64) -> f64 {
+    legendre_values(k, t)[k]
+}
+
+/// Returns the Legendre coefficients of the derivative (with respect to t) of the series given by lambdas.
+/// Follows from (2k + 1) L_k = L'_{k+1} - L'_{k-1}
+/// ### d_k = (2k + 1) * (lambda_{k+1} + d_{k+2} / (2k + 5))
+pub fn derivative_coefficients(lambdas: &[f64]) -> Vec<f64> {
+    if lambdas.len() <= 1 {
+        return vec![0.];
+    }
+    let n = lambdas.len() - 1;
+    let mut out: Vec<f64> = vec![0.; n + 2];
+    for k in (0..n).rev() {
+        out[k] = (2 * k + 1) as f64 * (lambdas[k + 1] + out[k + 2] / (2 * k + 5) as f64);
     }
-    sum
+    out.truncate(n);
+    out
+}
+
+/// Returns the value of the approximating polynomial in point x from [a, b]
+pub fn legendre_approx_value(lambdas: &[f64], x: f64, interval: Interval) -> f64 {
+    clenshaw(lambdas, interval.to_unit(x))
 }
 
-pub fn get_coefficients(lambdas: &Vec<f64>) -> Vec<f64> {
-    let mut out: Vec<f64> = vec![0.; lambdas.len()];
-    for i in 0..lambdas.len() {
-        let mut temp: Vec<f64> = Vec::new();
-        let poly = legendre_polynomial(i);
-        for j in 0..poly.len() {
-            temp.push(poly[j] * lambdas[i]);
+/// Returns the value of the order-th derivative of the approximating polynomial in point x from [a, b].
+/// Every derivative with respect to t is scaled by dt/dx = 2 / (b - a).
+pub fn legendre_derivative_value(lambdas: &[f64], x: f64, interval: Interval, order: usize) -> f64 {
+    let mut coefficients = lambdas.to_vec();
+    for _ in 0..order {
+        coefficients = derivative_coefficients(&coefficients);
+    }
+    clenshaw(&coefficients, interval.to_unit(x)) * (2. / interval.width()).powi(order as i32)
+}
+
+/// Returns the coefficients of the approximating polynomial in the original variable x, highest power first
+pub fn get_coefficients(lambdas: &[f64], interval: Interval) -> Vec<f64> {
+    basis_to_monomial(lambdas, legendre_polynomial, Some(interval))
+}
+
+/// L2 norm of f minus the approximating polynomial on the interval, see `error_norms`
+pub fn approximation_error(f: &dyn TargetFunction, lambdas: &[f64], interval: Interval) -> f64 {
+    error_norms(f, &|x| legendre_approx_value(lambdas, x, interval), interval, &[], &norm_quadrature()).l2
+}
+
+/// Result of `best_approximation`
+#[derive(Debug, Clone)]
+pub struct DegreeSearch {
+    /// Chosen degree, the first one within the tolerance or the one with the smallest error
+    pub degree: usize,
+    /// Lambdas of the chosen degree
+    pub lambdas: Vec<f64>,
+    /// Whether the chosen degree is within the tolerance
+    pub converged: bool,
+    /// Norm the tolerance refers to
+    pub norm: ErrorNorm,
+    /// Every degree tried with its errors, in increasing degree
+    pub tried: Vec<(usize, ErrorNorms)>,
+}
+
+/// Searches the lowest degree whose approximation is within tolerance in the chosen norm.
+/// By orthogonality the lambdas don't depend on the degree, so every step only integrates one more lambda.
+/// Rules with a fixed amount of nodes have to resolve L_max_degree, Gauss-Legendre needs more than max_degree nodes.
+/// Stops at max_degree and returns the degree with the smallest error then, NaN errors count as infinite.
+/// * norm - the errors are measured with `error_norms`
+/// * quadrature, analytic_norm - see `calculate_lambdas`
+pub fn best_approximation(f: &dyn TargetFunction, tolerance: f64, norm: ErrorNorm, max_degree: usize, interval: Interval, quadrature: &dyn Quadrature, analytic_norm: bool) -> DegreeSearch {
+    let breakpoints: Vec<f64> = f.breakpoints().iter().map(|x| interval.to_unit(*x)).collect();
+    let error_quadrature = norm_quadrature();
+    let mut lambdas: Vec<f64> = Vec::new();
+    let mut tried: Vec<(usize, ErrorNorms)> = Vec::new();
+    for degree in 0..=max_degree {
+        lambdas.push(lambda_with_error(f, degree, interval, quadrature, analytic_norm, &breakpoints).0);
+        let norms = error_norms(f, &|x| legendre_approx_value(&lambdas, x, interval), interval, &[], &error_quadrature);
+        let error = norm.of(&norms);
+        tried.push((degree, norms));
+        if error <= tolerance {
+            return DegreeSearch { degree, lambdas, converged: true, norm, tried };
         }
-        for (iter, elem) in temp.iter().rev().enumerate() {
-            out[lambdas.len() - 1 - iter] += elem;
+    }
+    let error = |norms: &ErrorNorms| match norm.of(norms) {
+        e if e.is_nan() => f64::INFINITY,
+        e => e,
+    };
+    let degree = tried.iter().min_by(|a, b| error(&a.1).total_cmp(&error(&b.1))).map_or(0, |(degree, _)| *degree);
+    lambdas.truncate(degree + 1);
+    DegreeSearch { degree, lambdas, converged: false, norm, tried }
+}
+
+/// Result of `truncate_by_decay`
+#[derive(Debug, Clone)]
+pub struct CoefficientCutoff {
+    /// All computed lambdas, up to the maximum degree
+    pub coefficients: Vec<f64>,
+    /// Lambdas kept, the approximation of the chosen degree
+    pub lambdas: Vec<f64>,
+    /// Whether the coefficients reached a plateau, otherwise all of them are kept
+    pub resolved: bool,
+}
+
+impl CoefficientCutoff {
+    pub fn degree(&self) -> usize {
+        self.lambdas.len() - 1
+    }
+}
+
+/// Lambdas up to poly_deg from the Gauss-Legendre rule with the given number of nodes, at least poly_deg + 1.
+/// The L_k are evaluated by recurrence, so unlike `calculate_lambdas` this stays accurate for high degrees.
+/// More nodes reduce the aliasing of the higher degrees onto the computed lambdas for functions with kinks.
+/// ### lambda_k = (2k + 1) / 2 * sum(w_i f(x(t_i)) L_k(t_i))
+pub fn gauss_lambdas(f: &dyn TargetFunction, poly_deg: usize, interval: Interval, nodes: usize) -> Vec<f64> {
+    let (nodes, weights) = gauss_legendre(nodes.max(poly_deg + 1));
+    let mut out = vec![0.; poly_deg + 1];
+    for (t, w) in nodes.iter().zip(weights.iter()) {
+        let value = w * f.value(interval.from_unit(*t));
+        for (k, l) in legendre_values(poly_deg, *t).iter().enumerate() {
+            out[k] += value * l;
         }
     }
-    out
+    out.iter().enumerate().map(|(k, sum)| sum / legendre_norm(k)).collect()
+}
+
+/// Computes the lambdas up to max_degree with `gauss_lambdas` and cuts them off where they reach
+/// their noise floor, see `chop_coefficients`. Without a plateau all of them are kept.
+/// * tolerance - relative size of the noise floor, close to the accuracy of the lambdas
+/// * nodes - Gauss nodes of `gauss_lambdas`, more than max_degree + 1 keep the tail of functions with kinks
+///   from aliasing into a false plateau
+pub fn truncate_by_decay(f: &dyn TargetFunction, max_degree: usize, interval: Interval, tolerance: f64, nodes: usize) -> CoefficientCutoff {
+    let coefficients = gauss_lambdas(f, max_degree, interval, nodes);
+    let (length, resolved) = match chop_coefficients(&coefficients, tolerance) {
+        Some(length) => (length, true),
+        None => (coefficients.len(), false),
+    };
+    CoefficientCutoff { lambdas: coefficients[..length].to_vec(), coefficients, resolved }
+}
+
+/// Returns how many leading coefficients to keep, None when they don't reach a plateau.
+/// Follows Chebfun's standardChop: the monotone envelope of |lambda_k| / max|lambda| has a plateau at j
+/// when it is below tolerance^(2/3) there and decays by less than a factor
+/// ### r = 3 (1 - log(envelope_j) / log(tolerance))
+/// up to j2 = 1.25 j + 5. The cutoff is where the envelope plus a line rising by log10(tolerance^(-1/3))
+/// is lowest, which is the start of the plateau. Needs at least 17 coefficients.
+/// https://arxiv.org/abs/1512.01803
+pub fn chop_coefficients(coefficients: &[f64], tolerance: f64) -> Option<usize> {
+    let n = coefficients.len();
+    if n < 17 {
+        return None;
+    }
+    if tolerance >= 1. {
+        return Some(1);
+    }
+    let scale = coefficients.iter().fold(0., |max: f64, c| max.max(c.abs()));
+    if scale == 0. {
+        return Some(1);
+    }
+    let mut envelope: Vec<f64> = coefficients.iter().map(|c| c.abs() / scale).collect();
+    for k in (0..(n - 1)).rev() {
+        envelope[k] = envelope[k].max(envelope[k + 1]);
+    }
+
+    // the indices j, j2 and plateau count from 1 like in the paper
+    let mut plateau = 0;
+    let mut j2 = 0;
+    for j in 2..=n {
+        j2 = (1.25 * j as f64 + 5.).round() as usize;
+        if j2 > n {
+            return None;
+        }
+        let (e1, e2) = (envelope[j - 1], envelope[j2 - 1]);
+        let r = 3. * (1. - e1.ln() / tolerance.ln());
+        if e1 == 0. || e2 / e1 > r {
+            plateau = j - 1;
+            break;
+        }
+    }
+    if envelope[plateau - 1] == 0. {
+        return Some(plateau);
+    }
+
+    let floor = tolerance.powf(7. / 6.);
+    let j3 = envelope.iter().filter(|e| **e >= floor).count();
+    if j3 < j2 {
+        j2 = j3 + 1;
+        envelope[j2 - 1] = floor;
+    }
+    let rise = -tolerance.log10() / 3.;
+    let lowest = (0..j2)
+        .map(|i| envelope[i].log10() + rise * i as f64 / (j2 - 1) as f64)
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(i, _)| i);
+    Some(lowest.max(1))
+}
+
+/// Legendre series sum(lambdas[k] * L_k(t(x))) on an interval, the approximating polynomial as a value.
+/// Sums, products and compositions stay in the Legendre basis, so they don't suffer from
+/// the cancellation of the monomial coefficients at higher degrees.
+/// Series combined with each other have to live on the same interval.
+/// There is always at least one lambda.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendreSeries {
+    lambdas: Vec<f64>,
+    interval: Interval,
+}
+
+impl LegendreSeries {
+    /// Series with the given lambdas, an empty vector is the zero series
+    pub fn new(lambdas: Vec<f64>, interval: Interval) -> LegendreSeries {
+        let lambdas = if lambdas.is_empty() { vec![0.] } else { lambdas };
+        LegendreSeries { lambdas, interval }
+    }
+
+    pub fn constant(value: f64, interval: Interval) -> LegendreSeries {
+        LegendreSeries::new(vec![value], interval)
+    }
+
+    /// The identity x on the interval, x = (a + b) / 2 + (b - a) / 2 * L_1(t)
+    pub fn identity(interval: Interval) -> LegendreSeries {
+        LegendreSeries::new(vec![0.5 * (interval.a + interval.b), 0.5 * interval.width()], interval)
+    }
+
+    pub fn lambdas(&self) -> &[f64] {
+        &self.lambdas
+    }
+
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// Highest degree with a stored lambda, trailing zeros included
+    pub fn degree(&self) -> usize {
+        self.lambdas.len() - 1
+    }
+
+    /// Value in point x, see `legendre_approx_value`
+    pub fn value(&self, x: f64) -> f64 {
+        legendre_approx_value(&self.lambdas, x, self.interval)
+    }
+
+    /// Derivative with respect to x, see `derivative_coefficients`
+    pub fn derivative(&self) -> LegendreSeries {
+        let scale = 2. / self.interval.width();
+        LegendreSeries::new(derivative_coefficients(&self.lambdas).iter().map(|d| d * scale).collect(), self.interval)
+    }
+
+    /// Keeps the lambdas up to degree, which is the best approximation of that degree in L2
+    pub fn truncate(&self, degree: usize) -> LegendreSeries {
+        LegendreSeries::new(self.lambdas.iter().take(degree + 1).copied().collect(), self.interval)
+    }
+
+    /// Coefficients in the original variable x, highest power first, see `get_coefficients`
+    pub fn coefficients(&self) -> Vec<f64> {
+        get_coefficients(&self.lambdas, self.interval)
+    }
+
+    /// Returns self(inner(x)) on the interval of inner. The values of inner should lie in the interval of self.
+    /// Clenshaw's recurrence of `clenshaw` is run with series in place of numbers,
+    /// t is inner mapped onto [-1, 1]. The result has degree self.degree() * inner.degree().
+    pub fn compose(&self, inner: &LegendreSeries) -> LegendreSeries {
+        let width = self.interval.width();
+        let mut t = inner * (2. / width);
+        t.lambdas[0] -= (self.interval.a + self.interval.b) / width;
+
+        let mut b1 = LegendreSeries::constant(0., inner.interval);
+        let mut b2 = LegendreSeries::constant(0., inner.interval);
+        for k in (0..self.lambdas.len()).rev() {
+            let mut b0 = &(&t * &b1) * ((2 * k + 1) as f64 / (k + 1) as f64) - &b2 * ((k + 1) as f64 / (k + 2) as f64);
+            b0.lambdas[0] += self.lambdas[k];
+            b2 = b1;
+            b1 = b0;
+        }
+        // the first steps multiply zero series, which only leaves zeros above this degree
+        b1.truncate(self.degree() * inner.degree())
+    }
+
+    fn check_interval(&self, other: &LegendreSeries) {
+        assert_eq!(self.interval, other.interval, "Legendre series on different intervals");
+    }
+}
+
+/// Linearization coefficients of L_m L_n = sum(a_mnk L_k), k = |m - n|, |m - n| + 2, .., m + n.
+/// Adams' formula with s = (m + n + k) / 2
+/// ### a_mnk = A(s - m) A(s - n) A(s - k) / A(s) * (2k + 1) / (2s + 1), A(r) = 1 * 3 * .. * (2r - 1) / r!
+/// A(r) grows like 2^r and overflows for r above about 1000, so A(r) / 2^r = (1/2)_r / r! is used,
+/// which stays below 1. The powers of 2 cancel, because (s - m) + (s - n) + (s - k) = s.
+/// Returns (k, a_mnk) pairs.
+pub fn linearization_coefficients(m: usize, n: usize) -> Vec<(usize, f64)> {
+    linearization_with(&linearization_table(m + n), m, n)
+}
+
+/// A(0) / 2^0 .. A(max) / 2^max of `linearization_coefficients`, A(r) / 2^r = A(r - 1) / 2^(r - 1) * (2r - 1) / (2r)
+fn linearization_table(max: usize) -> Vec<f64> {
+    let mut a = vec![1.; max + 1];
+    for r in 1..a.len() {
+        a[r] = a[r - 1] * (2 * r - 1) as f64 / (2 * r) as f64;
+    }
+    a
+}
+
+/// `linearization_coefficients` with a table from `linearization_table` reaching at least m + n
+fn linearization_with(a: &[f64], m: usize, n: usize) -> Vec<(usize, f64)> {
+    (m.abs_diff(n)..=(m + n))
+        .step_by(2)
+        .map(|k| {
+            let s = (m + n + k) / 2;
+            (k, a[s - m] * a[s - n] * a[s - k] / a[s] * (2 * k + 1) as f64 / (2 * s + 1) as f64)
+        })
+        .collect()
+}
+
+impl Add for &LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn add(self, other: &LegendreSeries) -> LegendreSeries {
+        self.check_interval(other);
+        let mut lambdas = vec![0.; self.lambdas.len().max(other.lambdas.len())];
+        for (k, lambda) in self.lambdas.iter().enumerate() {
+            lambdas[k] += lambda;
+        }
+        for (k, lambda) in other.lambdas.iter().enumerate() {
+            lambdas[k] += lambda;
+        }
+        LegendreSeries::new(lambdas, self.interval)
+    }
+}
+
+impl Add for LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn add(self, other: LegendreSeries) -> LegendreSeries {
+        &self + &other
+    }
+}
+
+impl Neg for &LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn neg(self) -> LegendreSeries {
+        self * -1.
+    }
+}
+
+impl Neg for LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn neg(self) -> LegendreSeries {
+        -&self
+    }
+}
+
+impl Sub for &LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn sub(self, other: &LegendreSeries) -> LegendreSeries {
+        self + &(-other)
+    }
+}
+
+impl Sub for LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn sub(self, other: LegendreSeries) -> LegendreSeries {
+        &self - &other
+    }
+}
+
+impl Mul<f64> for &LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn mul(self, scalar: f64) -> LegendreSeries {
+        LegendreSeries::new(self.lambdas.iter().map(|lambda| lambda * scalar).collect(), self.interval)
+    }
+}
+
+impl Mul<f64> for LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn mul(self, scalar: f64) -> LegendreSeries {
+        &self * scalar
+    }
+}
+
+impl Div<f64> for &LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn div(self, scalar: f64) -> LegendreSeries {
+        self * (1. / scalar)
+    }
 }
 
-pub fn best_approximation(f: Function, eps: f64) -> (usize, bool) {
-    let mut poly_deg = 1;
-    let mut integral_nodes = 40;
-    let mut results: Vec<(usize, f64)> = Vec::new();
-    let mut error = eps + 1.;
-    while error > eps {
-        let lambdas = calculate_lambdas(f, poly_deg, integral_nodes);
-
-        // part copied from main, that just calculates the error.
-        let min = -1.;
-        let max = 1.;
-        let mut sum = 0.;
-        let step = (max - min) / poly_deg as f64;
-        for i in 0..poly_deg {
-            sum += (function_value(min + i as f64 * step, f) - legendre_approx_value(&lambdas, min + i as f64 * step)).powi(2);
+impl Div<f64> for LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn div(self, scalar: f64) -> LegendreSeries {
+        &self / scalar
+    }
+}
+
+/// Product of two series, expanded with `linearization_coefficients`, has the sum of their degrees
+impl Mul for &LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn mul(self, other: &LegendreSeries) -> LegendreSeries {
+        self.check_interval(other);
+        let mut lambdas = vec![0.; self.degree() + other.degree() + 1];
+        let table = linearization_table(self.degree() + other.degree());
+        for (m, left) in self.lambdas.iter().enumerate() {
+            if *left == 0. {
+                continue;
+            }
+            for (n, right) in other.lambdas.iter().enumerate() {
+                for (k, a) in linearization_with(&table, m, n) {
+                    lambdas[k] += left * right * a;
+                }
+            }
         }
-        error = sum.sqrt();
-        // end
-        results.push((poly_deg, error));
-        
-        if poly_deg >= 10 {
-            results.sort_by(
-                |a, b| 
-                a.1.partial_cmp(&b.1).unwrap());
-            poly_deg = results[0].0;
-            return (poly_deg, false);
+        LegendreSeries::new(lambdas, self.interval)
+    }
+}
+
+impl Mul for LegendreSeries {
+    type Output = LegendreSeries;
+
+    fn mul(self, other: LegendreSeries) -> LegendreSeries {
+        &self * &other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{functions::SimpleFunction, gauss::GaussLegendre};
+
+    fn close(left: f64, right: f64) -> bool {
+        (left - right).abs() <= 1e-12 * (1. + left.abs().max(right.abs()))
+    }
+
+    #[test]
+    fn high_degree_lambdas_match_the_gauss_ones() {
+        let sin = SimpleFunction { name: "Sinusoidal", value: f64::sin, derivative: None, breakpoints: &[] };
+        let interval = Interval::new(-1., 1.);
+        let lambdas = calculate_lambdas(&sin, 40, interval, &GaussLegendre::new(41), false);
+        for (lambda, reference) in lambdas.iter().zip(gauss_lambdas(&sin, 40, interval, 41)) {
+            assert!((lambda - reference).abs() < 1e-14, "{} != {}", lambda, reference);
         }
+        for (numeric, analytic) in norm_discrepancies(40, &GaussLegendre::new(41)) {
+            assert!(close(numeric, analytic));
+        }
+    }
 
-        poly_deg += 1;
-        integral_nodes += 1;
+    #[test]
+    fn squares_linear_polynomial() {
+        let product = linearization_coefficients(1, 1);
+        assert_eq!(product.len(), 2);
+        assert_eq!(product[0].0, 0);
+        assert!(close(product[0].1, 1. / 3.));
+        assert_eq!(product[1].0, 2);
+        assert!(close(product[1].1, 2. / 3.));
+    }
+
+    #[test]
+    fn linearization_stays_finite_at_high_degrees() {
+        // L_k(1) = 1, so the coefficients of every product add up to 1
+        for (m, n) in [(600, 600), (1000, 300), (2000, 2000)] {
+            let coefficients = linearization_coefficients(m, n);
+            assert!(coefficients.iter().all(|(_, a)| a.is_finite() && *a > 0.));
+            assert!((coefficients.iter().map(|(_, a)| a).sum::<f64>() - 1.).abs() < 1e-9);
+        }
     }
-    (poly_deg, true)
-}
\ No newline at end of file
+
+    #[test]
+    fn product_matches_pointwise_product() {
+        let interval = Interval::new(-2., 3.);
+        let left = LegendreSeries::new(vec![0.5, -1.25, 0.75, 0.3, -0.2], interval);
+        let right = LegendreSeries::new(vec![-1., 0.4, 2.], interval);
+        let product = &left * &right;
+        assert_eq!(product.degree(), 6);
+        for i in 0..=20 {
+            let x = interval.a + interval.width() * i as f64 / 20.;
+            assert!(close(product.value(x), left.value(x) * right.value(x)));
+        }
+    }
+
+    #[test]
+    fn compose_matches_nested_evaluation() {
+        let outer = LegendreSeries::new(vec![1., -0.5, 0.25, 0.125], Interval::new(-1., 4.));
+        let inner_interval = Interval::new(0., 2.);
+        let inner = LegendreSeries::new(vec![1.5, 1., 0.5], inner_interval);
+        let composed = outer.compose(&inner);
+        assert_eq!(composed.degree(), 6);
+        for i in 0..=20 {
+            let x = inner_interval.a + inner_interval.width() * i as f64 / 20.;
+            assert!(close(composed.value(x), outer.value(inner.value(x))));
+        }
+    }
+
+    #[test]
+    fn empty_lambdas_are_the_zero_series() {
+        let zero = LegendreSeries::new(Vec::new(), Interval::new(0., 1.));
+        assert_eq!(zero.degree(), 0);
+        assert_eq!((&zero * &zero).lambdas(), &[0.]);
+    }
+}
@@ -0,0 +1,46 @@
+pub mod functions;
+pub mod legendre;
+pub mod integral;
+pub mod gauss;
+pub mod expression;
+pub mod chebyshev;
+pub mod laguerre;
+pub mod hermite;
+pub mod orthogonal;
+pub mod linalg;
+pub mod remez;
+pub mod rational;
+pub mod fit;
+pub mod csv;
+pub mod piecewise;
+pub mod surface;
+pub mod error_analysis;
+pub mod convergence;
+
+/// Closed interval [a, b] the approximation takes place on.
+/// Legendre polynomials live on [-1, 1], so every computation maps the interval affinely onto it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Interval {
+    pub fn new(a: f64, b: f64) -> Interval {
+        Interval { a, b }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.b - self.a
+    }
+
+    /// Maps x from [a, b] onto t from [-1, 1]
+    pub fn to_unit(&self, x: f64) -> f64 {
+        (2. * x - self.a - self.b) / self.width()
+    }
+
+    /// Maps t from [-1, 1] back onto x from [a, b]
+    pub fn from_unit(&self, t: f64) -> f64 {
+        0.5 * (self.a + self.b) + 0.5 * self.width() * t
+    }
+}
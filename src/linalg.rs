@@ -0,0 +1,154 @@
+/// Solves the square system A x = b with Gaussian elimination and partial pivoting.
+/// Returns None when the matrix is singular to working precision.
+/// * matrix - rows of A
+/// * rhs - right-hand side b
+pub fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    let scale = matrix
+        .iter()
+        .flat_map(|row| row.iter())
+        .fold(0., |max: f64, elem| max.max(elem.abs()));
+    if scale == 0. {
+        return None;
+    }
+
+    for col in 0..n {
+        // row with the largest pivot
+        let pivot = (col..n)
+            .max_by(|i, j| matrix[*i][col].abs().total_cmp(&matrix[*j][col].abs()))
+            .unwrap_or(col);
+        if matrix[pivot][col].abs() <= n as f64 * f64::EPSILON * scale {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (i, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            if factor == 0. {
+                continue;
+            }
+            for (elem, pivot_elem) in row.iter_mut().zip(pivot_row.iter()).skip(col) {
+                *elem -= factor * pivot_elem;
+            }
+            rhs[col + 1 + i] -= factor * rhs[col];
+        }
+    }
+
+    Some(back_substitution(&matrix, &rhs))
+}
+
+/// Solves the overdetermined system A x = b in the least-squares sense with Householder QR.
+/// Returns None when A doesn't have full column rank to working precision.
+/// * matrix - rows of A, at least as many as columns
+/// * rhs - right-hand side b
+pub fn least_squares(matrix: Vec<Vec<f64>>, rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let (r, qtb) = householder_qr(matrix, rhs)?;
+    Some(back_substitution(&r, &qtb))
+}
+
+/// Householder QR of A, applied to b on the way.
+/// Returns the square upper triangle R and the first columns-many entries of Q^T b,
+/// or None when A doesn't have full column rank to working precision.
+/// https://en.wikipedia.org/wiki/QR_decomposition#Using_Householder_reflections
+/// * matrix - rows of A, at least as many as columns
+/// * rhs - right-hand side b
+pub fn householder_qr(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<(Vec<Vec<f64>>, Vec<f64>)> {
+    let rows = rhs.len();
+    let cols = matrix.first().map_or(0, |row| row.len());
+    if rows < cols {
+        return None;
+    }
+    let scale = matrix
+        .iter()
+        .flat_map(|row| row.iter())
+        .fold(0., |max: f64, elem| max.max(elem.abs()));
+    if scale == 0. {
+        return None;
+    }
+
+    for col in 0..cols {
+        let norm = matrix[col..].iter().map(|row| row[col] * row[col]).sum::<f64>().sqrt();
+        if norm <= rows as f64 * f64::EPSILON * scale {
+            return None;
+        }
+        // reflect the column onto -sign(a_cc) * norm * e_c, the sign avoids cancellation
+        let alpha = -norm.copysign(matrix[col][col]);
+        let mut v: Vec<f64> = matrix[col..].iter().map(|row| row[col]).collect();
+        v[0] -= alpha;
+        let v_norm2: f64 = v.iter().map(|elem| elem * elem).sum();
+
+        for k in col..cols {
+            let dot: f64 = v.iter().zip(matrix[col..].iter()).map(|(v, row)| v * row[k]).sum();
+            let factor = 2. * dot / v_norm2;
+            for (v, row) in v.iter().zip(matrix[col..].iter_mut()) {
+                row[k] -= factor * v;
+            }
+        }
+        let dot: f64 = v.iter().zip(rhs[col..].iter()).map(|(v, b)| v * b).sum();
+        let factor = 2. * dot / v_norm2;
+        for (v, b) in v.iter().zip(rhs[col..].iter_mut()) {
+            *b -= factor * v;
+        }
+    }
+
+    matrix.truncate(cols);
+    rhs.truncate(cols);
+    Some((matrix, rhs))
+}
+
+/// Solves R x = b for a square upper triangular R
+pub fn back_substitution(r: &[Vec<f64>], rhs: &[f64]) -> Vec<f64> {
+    let n = rhs.len();
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let mut sum = rhs[row];
+        for k in (row + 1)..n {
+            sum -= r[row][k] * x[k];
+        }
+        x[row] = sum / r[row][row];
+    }
+    x
+}
+
+/// Returns the singular values of a matrix, largest first, with the one-sided Jacobi method.
+/// Columns are rotated pairwise until all of them are orthogonal, their norms are then the singular values.
+/// https://en.wikipedia.org/wiki/Jacobi_eigenvalue_algorithm#Singular_values
+/// * matrix - rows of the matrix
+pub fn singular_values(matrix: &[Vec<f64>]) -> Vec<f64> {
+    let cols = matrix.first().map_or(0, |row| row.len());
+    // work on the columns
+    let mut columns: Vec<Vec<f64>> = (0..cols).map(|k| matrix.iter().map(|row| row[k]).collect()).collect();
+    for _ in 0..60 {
+        let mut rotated = false;
+        for i in 0..cols {
+            for j in (i + 1)..cols {
+                let alpha: f64 = columns[i].iter().map(|v| v * v).sum();
+                let beta: f64 = columns[j].iter().map(|v| v * v).sum();
+                let gamma: f64 = columns[i].iter().zip(columns[j].iter()).map(|(u, v)| u * v).sum();
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (2. * gamma);
+                let t = zeta.signum() / (zeta.abs() + zeta.hypot(1.));
+                let c = 1. / t.hypot(1.);
+                let s = c * t;
+                let (left, right) = columns.split_at_mut(j);
+                for (u, v) in left[i].iter_mut().zip(right[0].iter_mut()) {
+                    let (ui, vj) = (*u, *v);
+                    *u = c * ui - s * vj;
+                    *v = s * ui + c * vj;
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+    let mut out: Vec<f64> = columns.iter().map(|column| column.iter().map(|v| v * v).sum::<f64>().sqrt()).collect();
+    out.sort_by(|a, b| b.total_cmp(a));
+    out
+}
@@ -1,63 +1,1329 @@
 use eframe::{
-    egui::{self, plot::{Points, Plot, Values, Value, Line, VLine}, Layout},
+    egui::{self, plot::{Plot, Values, Value, Line, VLine, HLine, Points}, Layout},
     epi::{App}, run_native,
 };
-use functions::function_value;
-use legendre::*;
-
-mod functions;
-mod legendre;
-mod integral;
+use laguere_approximation::{
+    chebyshev::*,
+    csv::{read_csv, CsvError, CsvOptions, Delimiter, Header},
+    expression::{Expression, ParseError},
+    fit::{fit_legendre, noisy_samples, LeastSquaresFit, Sample, SampledFunction},
+    hermite::*,
+    laguerre::*,
+    convergence::{estimate_convergence, ConvergenceEstimate, Decay},
+    error_analysis::{error_norms, norm_quadrature, ErrorNorm, ErrorNorms},
+    functions::{FunctionRegistry, TargetFunction},
+    integral::{make_quadrature, QuadratureKind},
+    legendre::*,
+    orthogonal::*,
+    piecewise::{adaptive_breakpoints, piecewise_approximation, uniform_breakpoints},
+    surface::{calculate_lambdas_2d, Truncation},
+    rational::{legendre_pade, rational_least_squares},
+    remez::{remez, MinimaxApproximation},
+    Interval,
+};
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Mode {
     Nodes,
     AproxError,
+    Chebyshev,
+    Laguerre,
+    Hermite,
+    Orthogonal,
+    Minimax,
+    Rational,
+    DataFit,
+    Piecewise,
+    Surface,
+    Decay,
+}
+
+/// Family chosen in the "Orthogonal" mode
+#[derive(Clone, Copy, PartialEq)]
+pub enum Family {
+    Jacobi,
+    Gegenbauer,
+}
+
+/// Construction used in the "Rational" mode
+#[derive(Clone, Copy, PartialEq)]
+pub enum RationalMethod {
+    Pade,
+    LeastSquares,
+}
+
+/// Function chosen in the "Function" group
+#[derive(Clone, Copy, PartialEq)]
+pub enum Target {
+    Registered(usize),
+    Expression,
+    /// Samples loaded from a file
+    Data,
+}
+
+/// Returns the chosen target function, None when the custom expression doesn't parse or no data is loaded.
+/// Takes the fields instead of the whole AppState so the other fields stay mutable.
+fn target_function<'a>(registry: &'a FunctionRegistry, expression: &'a Result<Expression, ParseError>, data: &'a Option<SampledFunction>, target: Target) -> Option<&'a dyn TargetFunction> {
+    match target {
+        Target::Registered(i) => Some(registry.get(i)),
+        Target::Expression => expression.as_ref().ok().map(|e| e as &dyn TargetFunction),
+        Target::Data => data.as_ref().map(|d| d as &dyn TargetFunction),
+    }
+}
+
+/// Samples g in 10000 equally spaced points of the interval for the plot
+fn plot_values(interval: Interval, g: &dyn Fn(f64) -> f64) -> Vec<Value> {
+    let min = interval.a;
+    let max = interval.b;
+    (0..10000)
+    .map(|i| {
+        let x = min + (i as f64 *
+        ((max) - (min)) / 10000.);
+        Value::new(x, g(x))
+    })
+    .collect()
+}
+
+/// Drag values for both ends of an interval. An edit that would make the left end reach the right one is undone,
+/// nothing else is clamped, so intervals loaded from data keep their ends however large or narrow.
+fn interval_ends_ui(ui: &mut egui::Ui, interval: &mut Interval, names: [&str; 2]) {
+    let previous = *interval;
+    ui.horizontal(|ui| {
+        ui.label(names[0]);
+        ui.add(egui::DragValue::new(&mut interval.a).speed(0.05));
+        ui.label(names[1]);
+        ui.add(egui::DragValue::new(&mut interval.b).speed(0.05));
+    });
+    if interval.a >= interval.b {
+        *interval = previous;
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Function {
-    Poly1,
-    Poly2,
-    PerfectFit,
-    Linear,
-    Sinusoidal,
-    Absolute,
-    Mixed,
+/// Shows the error norms, prefix names the approximation when there are several
+fn error_ui(ui: &mut egui::Ui, prefix: &str, norms: &ErrorNorms) {
+    let relative = |norm: Option<f64>| match norm {
+        Some(norm) => format!("{:.3e}", norm),
+        None => String::from("-"),
+    };
+    ui.label(format!("{}L2 Error: {:.6e} (relative {})", prefix, norms.l2, relative(norms.relative_l2)));
+    ui.label(format!("{}Max. Error: {:.6e} (relative {})", prefix, norms.max, relative(norms.relative_max)));
+    ui.label(format!("Largest Error at x = {:.6}", norms.argmax));
+}
+
+/// Degree up to which the lambdas are computed for the convergence analysis
+const CONVERGENCE_DEGREE: usize = 100;
+
+/// Gauss nodes per lambda for the convergence analysis, oversampled so kinks don't alias onto the higher lambdas
+const CONVERGENCE_OVERSAMPLING: usize = 4;
+
+/// Heat map cells per side
+const HEAT_MAP_CELLS: usize = 60;
+
+/// Color of u from [0, 1] on a blue - cyan - green - yellow - red scale, gray for NaN
+fn heat_color(u: f64) -> egui::Color32 {
+    const STOPS: [(f64, f64, f64); 5] = [(0., 0., 255.), (0., 255., 255.), (0., 255., 0.), (255., 255., 0.), (255., 0., 0.)];
+    if u.is_nan() {
+        return egui::Color32::GRAY;
+    }
+    let position = u.clamp(0., 1.) * (STOPS.len() - 1) as f64;
+    let i = (position as usize).min(STOPS.len() - 2);
+    let fraction = position - i as f64;
+    let mix = |a: f64, b: f64| (a + (b - a) * fraction) as u8;
+    egui::Color32::from_rgb(mix(STOPS[i].0, STOPS[i + 1].0), mix(STOPS[i].1, STOPS[i + 1].1), mix(STOPS[i].2, STOPS[i + 1].2))
+}
+
+/// Paints values[row][column] as colored cells, rows going up in y and columns going right in x,
+/// with the colors spread between min and max
+fn heat_map(ui: &mut egui::Ui, title: &str, values: &[Vec<f64>], min: f64, max: f64) {
+    ui.vertical(|ui| {
+        ui.label(title);
+        let (response, painter) = ui.allocate_painter(egui::vec2(220., 220.), egui::Sense::hover());
+        let rect = response.rect;
+        let rows = values.len();
+        for (r, row) in values.iter().enumerate() {
+            let cell = egui::vec2(rect.width() / row.len() as f32, rect.height() / rows as f32);
+            for (c, value) in row.iter().enumerate() {
+                let corner = egui::pos2(rect.left() + c as f32 * cell.x, rect.bottom() - (r + 1) as f32 * cell.y);
+                let u = if max > min { (value - min) / (max - min) } else { 0.5 };
+                painter.rect_filled(egui::Rect::from_min_size(corner, cell), 0., heat_color(u));
+            }
+        }
+        ui.label(format!("{:.3e} to {:.3e}", min, max));
+    });
+}
+
+/// Formats coefficients (highest power first) as " a_n x^n + ... + a_0 x^0", " 0" without any
+fn format_polynomial(poly: &[f64]) -> String {
+    if poly.is_empty() {
+        return String::from(" 0");
+    }
+    let deg = poly.len() - 1;
+    let mut polynomial: String = String::from(" ");
+    for (i, j) in poly.iter().enumerate() {
+        if i == deg  {
+            polynomial += format!("{:.3}x^{}" , j, deg - i).as_str();
+        } else {
+            polynomial += format!("{:.3}x^{} + " , j, deg - i).as_str();
+        }
+    }
+    polynomial
 }
 
 struct AppState {
-    function: Function,
+    registry: FunctionRegistry,
+    target: Target,
+    expression_source: String,
+    expression: Result<Expression, ParseError>,
     no_of_nodes: usize,
     mode: Mode,
     chosen_function_values: Vec<Value>,
     approx_values: Vec<Value>,
+    derivative_values: Vec<Value>,
+    show_derivative: bool,
     lambdas: Vec<f64>,
     center_plot: bool,
     integral_nodes: usize,
-    approx_error: f64,
+    approx_error: ErrorNorms,
     polynomial: String,
     given_approx_error: f64,
-    epsilon_flag: bool,
+    error_norm: ErrorNorm,
+    max_degree: usize,
+    search: Option<DegreeSearch>,
+    interval: Interval,
+    quadrature: QuadratureKind,
+    newton_cotes_order: usize,
+    quadrature_used: String,
+    quadrature_tolerance: f64,
+    lambda_errors: Vec<Option<f64>>,
+    analytic_norm: bool,
+    norm_diagnostics: bool,
+    norm_discrepancies: Vec<(f64, f64)>,
+    extra_lines: Vec<(String, Vec<Value>)>,
+    extra_labels: Vec<String>,
+    chebyshev_error: ErrorNorms,
+    markers: Vec<f64>,
+    laguerre_degree: usize,
+    laguerre_nodes: usize,
+    laguerre_window: f64,
+    hermite_kind: HermiteKind,
+    hermite_degree: usize,
+    hermite_nodes: usize,
+    hermite_window: f64,
+    family: Family,
+    jacobi_alpha: f64,
+    jacobi_beta: f64,
+    gegenbauer_lambda: f64,
+    family_degree: usize,
+    family_nodes: usize,
+    minimax: Option<MinimaxApproximation>,
+    error_values: Vec<Value>,
+    alternation_values: Vec<Value>,
+    rational_method: RationalMethod,
+    numerator_degree: usize,
+    denominator_degree: usize,
+    rational_failed: bool,
+    polynomial_error: ErrorNorms,
+    samples: Vec<Sample>,
+    sample_points: Vec<Value>,
+    sample_count: usize,
+    sample_noise: f64,
+    sample_seed: u64,
+    fit: Option<LeastSquaresFit>,
+    fit_failed: bool,
+    data: Option<SampledFunction>,
+    data_headers: Vec<String>,
+    csv_path: String,
+    csv_options: CsvOptions,
+    csv_error: Option<CsvError>,
+    element_count: usize,
+    breakpoints_source: String,
+    breakpoints_error: Option<String>,
+    continuous: bool,
+    max_jump: f64,
+    split_tolerance: f64,
+    surface_source: String,
+    surface: Result<Expression, ParseError>,
+    y_interval: Interval,
+    surface_degree: usize,
+    surface_nodes: usize,
+    truncation: Truncation,
+    /// target, approximation and error on the heat map grid
+    surface_values: [Vec<Vec<f64>>; 3],
+    surface_terms: usize,
+    surface_error: f64,
+    decay_degree: usize,
+    decay_tolerance: f64,
+    cutoff: Option<CoefficientCutoff>,
+    /// (k, log10 |lambda_k|) of the nonzero lambdas
+    coefficient_values: Vec<Value>,
+    convergence: Option<ConvergenceEstimate>,
+    prediction_tolerance: f64,
 }
 
 impl AppState {
     fn new() -> AppState {
         AppState {
-            function: Function::Poly1,
+            registry: FunctionRegistry::with_builtins(),
+            target: Target::Registered(0),
+            expression_source: String::from("sin(3 * x) + abs(x)"),
+            expression: Expression::parse("sin(3 * x) + abs(x)"),
             no_of_nodes: 2,
             mode: Mode::Nodes,
             chosen_function_values: Vec::new(),
             approx_values: Vec::new(),
+            derivative_values: Vec::new(),
+            show_derivative: false,
             lambdas: Vec::new(),
             center_plot: false,
             integral_nodes: 2,
-            approx_error: 0.,
+            approx_error: ErrorNorms::default(),
             polynomial: String::new(),
             given_approx_error: 0.1,
-            epsilon_flag: true,
+            error_norm: ErrorNorm::L2,
+            max_degree: 20,
+            search: None,
+            interval: Interval::new(-1., 1.),
+            quadrature: QuadratureKind::Simpson,
+            newton_cotes_order: 4,
+            quadrature_used: String::new(),
+            quadrature_tolerance: 1e-10,
+            lambda_errors: Vec::new(),
+            analytic_norm: true,
+            norm_diagnostics: false,
+            norm_discrepancies: Vec::new(),
+            extra_lines: Vec::new(),
+            extra_labels: Vec::new(),
+            chebyshev_error: ErrorNorms::default(),
+            markers: Vec::new(),
+            laguerre_degree: 6,
+            laguerre_nodes: 40,
+            laguerre_window: 10.,
+            hermite_kind: HermiteKind::Physicists,
+            hermite_degree: 8,
+            hermite_nodes: 40,
+            hermite_window: 4.,
+            family: Family::Jacobi,
+            jacobi_alpha: 0.,
+            jacobi_beta: 0.,
+            gegenbauer_lambda: 0.5,
+            family_degree: 6,
+            family_nodes: 40,
+            minimax: None,
+            error_values: Vec::new(),
+            alternation_values: Vec::new(),
+            rational_method: RationalMethod::Pade,
+            numerator_degree: 2,
+            denominator_degree: 2,
+            rational_failed: false,
+            polynomial_error: ErrorNorms::default(),
+            samples: Vec::new(),
+            sample_points: Vec::new(),
+            sample_count: 50,
+            sample_noise: 0.1,
+            sample_seed: 1,
+            fit: None,
+            fit_failed: false,
+            data: None,
+            data_headers: Vec::new(),
+            csv_path: String::new(),
+            csv_options: CsvOptions::default(),
+            csv_error: None,
+            element_count: 4,
+            breakpoints_source: String::new(),
+            breakpoints_error: None,
+            continuous: false,
+            max_jump: 0.,
+            split_tolerance: 1e-4,
+            surface_source: String::from("exp(-x^2 - 2 * y^2) + 0.2 * abs(x - y)"),
+            surface: Expression::parse_2d("exp(-x^2 - 2 * y^2) + 0.2 * abs(x - y)"),
+            y_interval: Interval::new(-1., 1.),
+            surface_degree: 8,
+            surface_nodes: 20,
+            truncation: Truncation::Total,
+            surface_values: [Vec::new(), Vec::new(), Vec::new()],
+            surface_terms: 0,
+            surface_error: 0.,
+            decay_degree: 100,
+            decay_tolerance: 1e-14,
+            cutoff: None,
+            coefficient_values: Vec::new(),
+            convergence: None,
+            prediction_tolerance: 1e-10,
+        }
+    }
+
+    /// Generates the plot values, the approximation error and the polynomial string
+    /// for already chosen function, degree and interval.
+    fn calculate(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![self.interval.a, self.interval.b];
+        // generating values of chosen function for the plot
+        self.chosen_function_values = plot_values(self.interval, &|x| f.value(x));
+
+        // generating values of approximated function for the plot
+        let quadrature = make_quadrature(self.quadrature, self.integral_nodes, self.newton_cotes_order, self.quadrature_tolerance);
+        (self.lambdas, self.lambda_errors) = calculate_lambdas_with_errors(f, self.no_of_nodes, self.interval, quadrature.as_ref(), self.analytic_norm);
+        self.norm_discrepancies = match self.norm_diagnostics {
+            true => norm_discrepancies(self.no_of_nodes, quadrature.as_ref()),
+            false => Vec::new(),
+        };
+        self.quadrature_used = quadrature.name();
+        self.approx_values = plot_values(self.interval, &|x| legendre_approx_value(&self.lambdas, x, self.interval));
+        self.derivative_values = plot_values(self.interval, &|x| legendre_derivative_value(&self.lambdas, x, self.interval, 1));
+
+        self.approx_error = error_norms(f, &|x| legendre_approx_value(&self.lambdas, x, self.interval), self.interval, &[], &norm_quadrature());
+
+        self.polynomial = format_polynomial(&get_coefficients(&self.lambdas, self.interval));
+        self.convergence = estimate_convergence(&gauss_lambdas(f, CONVERGENCE_DEGREE, self.interval, CONVERGENCE_OVERSAMPLING * (CONVERGENCE_DEGREE + 1)));
+    }
+
+    /// Calculates the Legendre approximation and adds the Chebyshev one of the same degree for comparison
+    fn calculate_chebyshev(&mut self) {
+        self.calculate();
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let coeffs = chebyshev_coefficients(f, self.no_of_nodes, self.interval);
+        self.chebyshev_error = error_norms(f, &|x| chebyshev_approx_value(&coeffs, x, self.interval), self.interval, &[], &norm_quadrature());
+        self.extra_lines.push((
+            String::from("Chebyshev Approx."),
+            plot_values(self.interval, &|x| chebyshev_approx_value(&coeffs, x, self.interval)),
+        ));
+        self.extra_labels.push(format!("Chebyshev:{}", format_polynomial(&get_chebyshev_coefficients(&coeffs, self.interval))));
+    }
+
+    /// Projects the target onto the Laguerre polynomials and plots it over the window [0, laguerre_window]
+    fn calculate_laguerre(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let window = Interval::new(0., self.laguerre_window);
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![0.];
+        self.lambdas = Vec::new();
+        self.lambda_errors = Vec::new();
+        self.derivative_values = Vec::new();
+
+        let coeffs = laguerre_coefficients(f, self.laguerre_degree, self.laguerre_nodes);
+        self.chosen_function_values = plot_values(window, &|x| f.value(x));
+        self.approx_values = plot_values(window, &|x| laguerre_approx_value(&coeffs, x));
+        self.approx_error = error_norms(f, &|x| laguerre_approx_value(&coeffs, x), window, &[], &norm_quadrature());
+        self.polynomial = format_polynomial(&get_laguerre_coefficients(&coeffs));
+        self.quadrature_used = format!("Gauss-Laguerre ({} nodes)", self.laguerre_nodes);
+    }
+
+    fn laguerre_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.group(|ui| {
+                ui.label("Projection onto L_k with weight e^(-x) on [0, ∞)");
+                ui.label("Plot Window [0, X]");
+                ui.add(egui::Slider::new(&mut self.laguerre_window, 1.0..=50.0));
+            });
+            ui.group(|ui| {
+                ui.label("Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.laguerre_degree, 0..=30));
+                ui.label("Gauss-Laguerre Nodes");
+                ui.add(egui::Slider::new(&mut self.laguerre_nodes, 2..=100));
+            });
+            if ui.button("Calculate").clicked() {
+                if self.laguerre_nodes <= self.laguerre_degree {
+                    self.laguerre_nodes = self.laguerre_degree + 1;
+                }
+                self.calculate_laguerre();
+            }
+            ui.group(|ui| {
+                error_ui(ui, "", &self.approx_error);
+                ui.label(self.quadrature_used.as_str());
+            });
+        });
+    }
+
+    /// Projects the target onto the Hermite polynomials and plots it over the window [-hermite_window, hermite_window]
+    fn calculate_hermite(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let window = Interval::new(-self.hermite_window, self.hermite_window);
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = Vec::new();
+        self.lambdas = Vec::new();
+        self.lambda_errors = Vec::new();
+        self.derivative_values = Vec::new();
+
+        let kind = self.hermite_kind;
+        let coeffs = hermite_coefficients(f, self.hermite_degree, self.hermite_nodes, kind);
+        self.chosen_function_values = plot_values(window, &|x| f.value(x));
+        self.approx_values = plot_values(window, &|x| hermite_approx_value(&coeffs, x, kind));
+        self.approx_error = error_norms(f, &|x| hermite_approx_value(&coeffs, x, kind), window, &[], &norm_quadrature());
+        self.polynomial = format_polynomial(&get_hermite_coefficients(&coeffs, kind));
+        self.quadrature_used = format!("Gauss-Hermite ({} nodes)", self.hermite_nodes);
+    }
+
+    fn hermite_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.group(|ui| {
+                ui.radio_value(&mut self.hermite_kind, HermiteKind::Physicists, "Physicists' H_n, weight e^(-x²)");
+                ui.radio_value(&mut self.hermite_kind, HermiteKind::Probabilists, "Probabilists' He_n, weight e^(-x²/2)");
+                ui.label("Plot Window [-X, X]");
+                ui.add(egui::Slider::new(&mut self.hermite_window, 1.0..=20.0));
+            });
+            ui.group(|ui| {
+                ui.label("Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.hermite_degree, 0..=30));
+                ui.label("Gauss-Hermite Nodes");
+                ui.add(egui::Slider::new(&mut self.hermite_nodes, 2..=100));
+            });
+            if ui.button("Calculate").clicked() {
+                if self.hermite_nodes <= self.hermite_degree {
+                    self.hermite_nodes = self.hermite_degree + 1;
+                }
+                self.calculate_hermite();
+            }
+            ui.group(|ui| {
+                error_ui(ui, "", &self.approx_error);
+                ui.label(self.quadrature_used.as_str());
+            });
+        });
+    }
+
+    /// Projects the target onto the chosen Jacobi or Gegenbauer family on the interval
+    fn calculate_orthogonal(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let family: Box<dyn OrthogonalFamily> = match self.family {
+            Family::Jacobi => Box::new(Jacobi::new(self.jacobi_alpha, self.jacobi_beta)),
+            Family::Gegenbauer => Box::new(Gegenbauer::new(self.gegenbauer_lambda)),
+        };
+        let family = family.as_ref();
+        let interval = self.interval;
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![interval.a, interval.b];
+        self.lambdas = Vec::new();
+        self.lambda_errors = Vec::new();
+        self.derivative_values = Vec::new();
+
+        let coeffs = family_coefficients(family, f, self.family_degree, self.family_nodes, interval);
+        self.chosen_function_values = plot_values(interval, &|x| f.value(x));
+        self.approx_values = plot_values(interval, &|x| family_approx_value(family, &coeffs, x, interval));
+        self.approx_error = error_norms(f, &|x| family_approx_value(family, &coeffs, x, interval), interval, &[], &norm_quadrature());
+        self.polynomial = format_polynomial(&get_family_coefficients(family, &coeffs, interval));
+        self.quadrature_used = format!("Gauss {} ({} nodes)", family.name(), self.family_nodes);
+    }
+
+    fn orthogonal_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.radio_value(&mut self.family, Family::Jacobi, "Jacobi P_n^(α,β), weight (1-t)^α (1+t)^β");
+                ui.radio_value(&mut self.family, Family::Gegenbauer, "Gegenbauer C_n^(λ), weight (1-t²)^(λ-1/2)");
+                match self.family {
+                    Family::Jacobi => {
+                        ui.label("α");
+                        ui.add(egui::Slider::new(&mut self.jacobi_alpha, -0.95..=5.0));
+                        ui.label("β");
+                        ui.add(egui::Slider::new(&mut self.jacobi_beta, -0.95..=5.0));
+                    },
+                    Family::Gegenbauer => {
+                        ui.label("λ");
+                        ui.add(egui::Slider::new(&mut self.gegenbauer_lambda, 0.05..=5.0));
+                    },
+                }
+            });
+            ui.group(|ui| {
+                ui.label("Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.family_degree, 0..=30));
+                ui.label("Gauss Nodes");
+                ui.add(egui::Slider::new(&mut self.family_nodes, 2..=100));
+            });
+            if ui.button("Calculate").clicked() {
+                if self.family_nodes <= self.family_degree {
+                    self.family_nodes = self.family_degree + 1;
+                }
+                self.calculate_orthogonal();
+            }
+            ui.group(|ui| {
+                error_ui(ui, "", &self.approx_error);
+                ui.label(self.quadrature_used.as_str());
+            });
+        });
+    }
+
+    /// Calculates the minimax polynomial of the chosen degree, seeded with the Legendre lambdas,
+    /// and the error curve for the second plot
+    fn calculate_minimax(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let interval = self.interval;
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![interval.a, interval.b];
+        self.lambda_errors = Vec::new();
+        self.norm_discrepancies = Vec::new();
+
+        let quadrature = make_quadrature(self.quadrature, self.integral_nodes, self.newton_cotes_order, self.quadrature_tolerance);
+        let minimax = remez(f, self.no_of_nodes, interval, quadrature.as_ref(), self.analytic_norm, 50);
+        self.quadrature_used = quadrature.name();
+        self.lambdas = minimax.lambdas.clone();
+        self.chosen_function_values = plot_values(interval, &|x| f.value(x));
+        self.approx_values = plot_values(interval, &|x| legendre_approx_value(&minimax.lambdas, x, interval));
+        self.derivative_values = plot_values(interval, &|x| legendre_derivative_value(&minimax.lambdas, x, interval, 1));
+        self.error_values = plot_values(interval, &|x| f.value(x) - legendre_approx_value(&minimax.lambdas, x, interval));
+        self.alternation_values = minimax.reference
+            .iter()
+            .map(|x| Value::new(*x, f.value(*x) - legendre_approx_value(&minimax.lambdas, *x, interval)))
+            .collect();
+        self.approx_error = error_norms(f, &|x| legendre_approx_value(&minimax.lambdas, x, interval), interval, &[], &norm_quadrature());
+        self.polynomial = format_polynomial(&get_coefficients(&minimax.lambdas, interval));
+        self.minimax = Some(minimax);
+    }
+
+    fn minimax_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.label("Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.no_of_nodes, 0..=20));
+                ui.label("Integral Nodes (Least-Squares Seed)");
+                ui.add(egui::Slider::new(&mut self.integral_nodes, 2..=40));
+            });
+            self.quadrature_ui(ui);
+            if ui.button("Calculate").clicked() {
+                if self.integral_nodes <= self.no_of_nodes {
+                    self.integral_nodes = self.no_of_nodes + 1;
+                }
+                self.calculate_minimax();
+            }
+            if let Some(minimax) = &self.minimax {
+                ui.group(|ui| {
+                    if !minimax.converged {
+                        ui.label("The exchange didn't converge. Displaying the last iterate.");
+                    }
+                    ui.label(format!("Equioscillation Error E: {:.6e}", minimax.levelled_error));
+                    error_ui(ui, "", &self.approx_error);
+                    ui.label(format!("Iterations: {}", minimax.iterations));
+                    ui.label(self.quadrature_used.as_str());
+                });
+            }
+        });
+    }
+
+    /// Calculates the rational approximation and, for comparison, the Legendre polynomial
+    /// of the same total degree numerator_degree + denominator_degree
+    fn calculate_rational(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let interval = self.interval;
+        let (m, n) = (self.numerator_degree, self.denominator_degree);
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![interval.a, interval.b];
+        self.lambda_errors = Vec::new();
+        self.norm_discrepancies = Vec::new();
+        self.derivative_values = Vec::new();
+
+        // Pade needs the lambdas up to degree m + 2n, the comparison polynomial the first m + n + 1 of them
+        let quadrature = make_quadrature(self.quadrature, self.integral_nodes, self.newton_cotes_order, self.quadrature_tolerance);
+        let lambdas = calculate_lambdas(f, m + 2 * n, interval, quadrature.as_ref(), self.analytic_norm);
+        self.quadrature_used = quadrature.name();
+        self.lambdas = lambdas[..=(m + n)].to_vec();
+        let rational = match self.rational_method {
+            RationalMethod::Pade => legendre_pade(&lambdas, m, n),
+            RationalMethod::LeastSquares => rational_least_squares(f, m, n, interval, 3),
+        };
+        self.rational_failed = rational.is_none();
+        let rational = match rational {
+            Some(rational) => rational,
+            None => return,
+        };
+
+        self.chosen_function_values = plot_values(interval, &|x| f.value(x));
+        self.approx_values = plot_values(interval, &|x| rational.value(x, interval));
+        self.extra_lines.push((
+            format!("Polynomial (degree {})", m + n),
+            plot_values(interval, &|x| legendre_approx_value(&self.lambdas, x, interval)),
+        ));
+        self.approx_error = error_norms(f, &|x| rational.value(x, interval), interval, &[], &norm_quadrature());
+        self.polynomial_error = error_norms(f, &|x| legendre_approx_value(&self.lambdas, x, interval), interval, &[], &norm_quadrature());
+        self.polynomial = format!("Numerator:{}", format_polynomial(&get_coefficients(&rational.numerator, interval)));
+        self.extra_labels.push(format!("Denominator:{}", format_polynomial(&get_coefficients(&rational.denominator, interval))));
+        self.extra_labels.push(format!("Polynomial:{}", format_polynomial(&get_coefficients(&self.lambdas, interval))));
+        for (re, im) in rational.poles(interval) {
+            self.extra_labels.push(format!("Pole: {:.6} {:+.6}i", re, im));
+            // real poles near the interval are marked in the plot
+            if im.abs() <= 1e-6 * interval.width() && re > interval.a - interval.width() && re < interval.b + interval.width() {
+                self.markers.push(re);
+            }
         }
     }
+
+    fn rational_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.radio_value(&mut self.rational_method, RationalMethod::Pade, "Legendre-Padé");
+                ui.radio_value(&mut self.rational_method, RationalMethod::LeastSquares, "Linearized Least Squares");
+                ui.label("Numerator Degree");
+                ui.add(egui::Slider::new(&mut self.numerator_degree, 0..=10));
+                ui.label("Denominator Degree");
+                ui.add(egui::Slider::new(&mut self.denominator_degree, 0..=10));
+                ui.label("Integral Nodes");
+                ui.add(egui::Slider::new(&mut self.integral_nodes, 2..=40));
+            });
+            self.quadrature_ui(ui);
+            if ui.button("Calculate").clicked() {
+                let deg = self.numerator_degree + 2 * self.denominator_degree;
+                if self.integral_nodes <= deg {
+                    self.integral_nodes = deg + 1;
+                }
+                self.calculate_rational();
+            }
+            ui.group(|ui| {
+                if self.rational_failed {
+                    ui.colored_label(egui::Color32::RED, "The linear system is singular, try lower degrees.");
+                }
+                error_ui(ui, "Rational ", &self.approx_error);
+                error_ui(ui, "Polynomial ", &self.polynomial_error);
+                ui.label(self.quadrature_used.as_str());
+            });
+        });
+    }
+
+    /// Takes the loaded samples, or samples the target with noise to stand in for measured data
+    fn generate_samples(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        match (self.target, &self.data) {
+            (Target::Data, Some(data)) => {
+                self.samples = data.samples().to_vec();
+                self.chosen_function_values = Vec::new();
+            },
+            _ => {
+                self.samples = noisy_samples(f, self.sample_count, self.sample_noise, self.interval, self.sample_seed);
+                self.chosen_function_values = plot_values(self.interval, &|x| f.value(x));
+            },
+        }
+        self.sample_points = self.samples.iter().map(|sample| Value::new(sample.x, sample.y)).collect();
+        self.approx_values = Vec::new();
+        self.fit = None;
+        self.fit_failed = false;
+    }
+
+    /// Fits the Legendre polynomial of degree no_of_nodes to the samples
+    fn calculate_fit(&mut self) {
+        if self.samples.is_empty() || self.target == Target::Data {
+            self.generate_samples();
+        }
+        let interval = self.interval;
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![interval.a, interval.b];
+        self.lambda_errors = Vec::new();
+        self.norm_discrepancies = Vec::new();
+        self.quadrature_used = String::new();
+
+        self.fit = fit_legendre(&self.samples, self.no_of_nodes, interval);
+        self.fit_failed = self.fit.is_none();
+        let fit = match &self.fit {
+            Some(fit) => fit,
+            None => return,
+        };
+        self.lambdas = fit.lambdas.clone();
+        self.lambda_errors = vec![None; fit.lambdas.len()];
+        self.approx_values = plot_values(interval, &|x| legendre_approx_value(&fit.lambdas, x, interval));
+        self.derivative_values = plot_values(interval, &|x| legendre_derivative_value(&fit.lambdas, x, interval, 1));
+        if let Some(f) = target_function(&self.registry, &self.expression, &self.data, self.target) {
+            self.approx_error = error_norms(f, &|x| legendre_approx_value(&fit.lambdas, x, interval), interval, &[], &norm_quadrature());
+        }
+        self.polynomial = format_polynomial(&get_coefficients(&fit.lambdas, interval));
+    }
+
+    fn data_fit_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                if self.target == Target::Data {
+                    ui.label("Fitting the loaded samples");
+                    return;
+                }
+                ui.label("Samples of the Chosen Function");
+                ui.add(egui::Slider::new(&mut self.sample_count, 1..=500));
+                ui.label("Noise (Standard Deviation)");
+                ui.add(egui::Slider::new(&mut self.sample_noise, 0.0..=1.0));
+                ui.label("Seed");
+                ui.add(egui::DragValue::new(&mut self.sample_seed));
+                if ui.button("Generate Samples").clicked() {
+                    self.generate_samples();
+                }
+            });
+            ui.group(|ui| {
+                ui.label("Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.no_of_nodes, 0..=20));
+            });
+            if ui.button("Fit").clicked() {
+                self.calculate_fit();
+            }
+            ui.group(|ui| {
+                match &self.fit {
+                    Some(fit) => {
+                        let max_residual = fit.residuals.iter().fold(0., |max: f64, r| max.max(r.abs()));
+                        ui.label(format!("RMS Residual: {:.6}", fit.rms));
+                        ui.label(format!("Max. Residual: {:.6}", max_residual));
+                        ui.label(format!("Condition Number: {:.3e}", fit.condition_number));
+                        ui.label("Distance to the chosen function:");
+                        error_ui(ui, "", &self.approx_error);
+                    },
+                    None if self.fit_failed => {
+                        ui.colored_label(egui::Color32::RED, "Not enough distinct samples for this degree.");
+                    },
+                    None => {
+                        ui.label(format!("{} samples", self.samples.len()));
+                    },
+                }
+                self.lambdas_ui(ui);
+            });
+        });
+    }
+
+    /// Approximates the target element by element, between the typed breakpoints
+    /// or, when none are typed, between element_count equally wide elements
+    fn calculate_piecewise(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let interval = self.interval;
+        let breakpoints: Result<Vec<f64>, String> = self.breakpoints_source
+            .split(',')
+            .map(|field| field.trim())
+            .filter(|field| !field.is_empty())
+            .map(|field| field.parse::<f64>().map_err(|_| format!("'{}' is not a number", field)))
+            .collect();
+        let breakpoints = match breakpoints {
+            Ok(breakpoints) if breakpoints.is_empty() => uniform_breakpoints(interval, self.element_count),
+            Ok(breakpoints) => breakpoints,
+            Err(error) => {
+                self.breakpoints_error = Some(error);
+                return;
+            },
+        };
+        self.breakpoints_error = None;
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.lambdas = Vec::new();
+        self.lambda_errors = Vec::new();
+        self.norm_discrepancies = Vec::new();
+        self.derivative_values = Vec::new();
+
+        let quadrature = make_quadrature(self.quadrature, self.integral_nodes, self.newton_cotes_order, self.quadrature_tolerance);
+        let piecewise = piecewise_approximation(f, self.no_of_nodes, interval, &breakpoints, quadrature.as_ref(), self.analytic_norm, self.continuous);
+        self.quadrature_used = quadrature.name();
+        self.markers = piecewise.boundaries();
+        self.chosen_function_values = plot_values(interval, &|x| f.value(x));
+        self.approx_values = plot_values(interval, &|x| piecewise.value(x));
+        self.approx_error = error_norms(f, &|x| piecewise.value(x), interval, &piecewise.boundaries(), &norm_quadrature());
+        self.max_jump = piecewise.max_jump();
+        self.polynomial = format!("{} elements", piecewise.elements.len());
+        for element in piecewise.elements.iter() {
+            self.extra_labels.push(format!(
+                "[{:.3}, {:.3}]:{}",
+                element.interval.a,
+                element.interval.b,
+                format_polynomial(&get_coefficients(&element.lambdas, element.interval)),
+            ));
+        }
+    }
+
+    /// Replaces the typed breakpoints by the ones found by adaptive splitting
+    fn detect_breakpoints(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let quadrature = make_quadrature(self.quadrature, self.integral_nodes, self.newton_cotes_order, self.quadrature_tolerance);
+        let breakpoints = adaptive_breakpoints(f, self.no_of_nodes, self.interval, self.split_tolerance, quadrature.as_ref(), self.analytic_norm);
+        self.breakpoints_source = breakpoints.iter().map(|x| format!("{}", x)).collect::<Vec<String>>().join(", ");
+    }
+
+    fn piecewise_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.label("Breakpoints (comma separated, empty for uniform elements)");
+                ui.text_edit_singleline(&mut self.breakpoints_source);
+                if let Some(error) = &self.breakpoints_error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                ui.label("Uniform Elements");
+                ui.add(egui::Slider::new(&mut self.element_count, 1..=20));
+                ui.checkbox(&mut self.continuous, "Continuous at Breakpoints");
+                ui.label("Splitting Tolerance (Coefficient Decay)");
+                ui.add(egui::Slider::new(&mut self.split_tolerance, 1e-10..=1e-1).logarithmic(true));
+                if ui.button("Detect Breakpoints").clicked() {
+                    // the degree is shared with the other tabs, whose sliders go below 3
+                    self.no_of_nodes = self.no_of_nodes.max(3);
+                    // the detection also computes the two lambdas above the degree
+                    if self.integral_nodes <= self.no_of_nodes + 2 {
+                        self.integral_nodes = self.no_of_nodes + 3;
+                    }
+                    self.detect_breakpoints();
+                    self.calculate_piecewise();
+                }
+            });
+            ui.group(|ui| {
+                ui.label("Polynomial Degree per Element");
+                ui.add(egui::Slider::new(&mut self.no_of_nodes, 3..=10));
+                ui.label("Integral Nodes");
+                ui.add(egui::Slider::new(&mut self.integral_nodes, 2..=40));
+            });
+            self.quadrature_ui(ui);
+            if ui.button("Calculate").clicked() {
+                if self.integral_nodes <= self.no_of_nodes {
+                    self.integral_nodes = self.no_of_nodes + 1;
+                }
+                self.calculate_piecewise();
+            }
+            ui.group(|ui| {
+                error_ui(ui, "", &self.approx_error);
+                ui.label(format!("Max. Jump at Breakpoints: {:.3e}", self.max_jump));
+                ui.label(self.quadrature_used.as_str());
+            });
+        });
+    }
+
+    /// Projects the surface onto the products of Legendre polynomials on interval x y_interval
+    /// and samples target, approximation and error for the heat maps
+    fn calculate_surface(&mut self) {
+        let f = match &self.surface {
+            Ok(f) => f,
+            Err(_) => return,
+        };
+        let (x_interval, y_interval) = (self.interval, self.y_interval);
+        let surface = calculate_lambdas_2d(f, self.surface_degree, x_interval, y_interval, self.truncation, self.surface_nodes);
+        // cell centers
+        let grid = |interval: Interval, i: usize| interval.a + interval.width() * (i as f64 + 0.5) / HEAT_MAP_CELLS as f64;
+        let sample = |g: &dyn Fn(f64, f64) -> f64| -> Vec<Vec<f64>> {
+            (0..HEAT_MAP_CELLS)
+                .map(|r| (0..HEAT_MAP_CELLS).map(|c| g(grid(x_interval, c), grid(y_interval, r))).collect())
+                .collect()
+        };
+        let target = sample(&|x, y| f.eval_2d(x, y));
+        let approx = sample(&|x, y| surface.value(x, y));
+        let error = sample(&|x, y| f.eval_2d(x, y) - surface.value(x, y));
+        self.surface_error = error.iter().flatten().fold(0., |max: f64, e| max.max(e.abs()));
+        self.surface_terms = surface.terms();
+        self.surface_values = [target, approx, error];
+    }
+
+    fn surface_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label("f(x, y) =");
+            if ui.text_edit_singleline(&mut self.surface_source).changed() {
+                self.surface = Expression::parse_2d(&self.surface_source);
+            }
+            if let Err(error) = &self.surface {
+                ui.colored_label(egui::Color32::RED, error.to_string());
+            }
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.label(format!("y from [{:.3}, {:.3}]", self.y_interval.a, self.y_interval.b));
+                interval_ends_ui(ui, &mut self.y_interval, ["c:", "d:"]);
+            });
+            ui.group(|ui| {
+                ui.radio_value(&mut self.truncation, Truncation::Total, "Total Degree (i + j ≤ n)");
+                ui.radio_value(&mut self.truncation, Truncation::Tensor, "Tensor Degree (i, j ≤ n)");
+                ui.label("Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.surface_degree, 0..=30));
+                ui.label("Gauss Nodes per Variable");
+                ui.add(egui::Slider::new(&mut self.surface_nodes, 2..=60));
+            });
+            if ui.button("Calculate").clicked() {
+                if self.surface_nodes <= self.surface_degree {
+                    self.surface_nodes = self.surface_degree + 1;
+                }
+                self.calculate_surface();
+            }
+            ui.group(|ui| {
+                ui.label(format!("Max. Error: {:.6e}", self.surface_error));
+                ui.label(format!("Terms: {}", self.surface_terms));
+            });
+        });
+    }
+
+    /// Target, approximation and error of the surface as heat maps
+    fn surface_view(&self, ui: &mut egui::Ui) {
+        let [target, approx, error] = &self.surface_values;
+        if target.is_empty() {
+            ui.label("Press Calculate to see the surface.");
+            return;
+        }
+        // target and approximation share the colors so they can be compared
+        let values = target.iter().chain(approx.iter()).flatten().filter(|v| v.is_finite());
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(*v), max.max(*v)));
+        ui.horizontal(|ui| {
+            heat_map(ui, "Target", target, min, max);
+            heat_map(ui, "Approximation", approx, min, max);
+            heat_map(ui, "Error", error, -self.surface_error, self.surface_error);
+        });
+        ui.label(format!("x from [{:.3}, {:.3}] to the right, y from [{:.3}, {:.3}] upwards",
+            self.interval.a, self.interval.b, self.y_interval.a, self.y_interval.b));
+    }
+
+    /// Searches the lowest degree within the given error and calculates its approximation
+    fn calculate_search(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let quadrature = make_quadrature(self.quadrature, self.integral_nodes, self.newton_cotes_order, self.quadrature_tolerance);
+        let search = best_approximation(f, self.given_approx_error, self.error_norm, self.max_degree, self.interval, quadrature.as_ref(), self.analytic_norm);
+        // the search already computed the lambdas and errors of the chosen degree
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![self.interval.a, self.interval.b];
+        self.no_of_nodes = search.degree;
+        self.lambdas = search.lambdas.clone();
+        self.lambda_errors = Vec::new();
+        self.norm_discrepancies = match self.norm_diagnostics {
+            true => norm_discrepancies(search.degree, quadrature.as_ref()),
+            false => Vec::new(),
+        };
+        self.quadrature_used = quadrature.name();
+        self.approx_error = search.tried.iter().find(|(degree, _)| *degree == search.degree).map(|(_, norms)| norms.clone()).unwrap_or_default();
+        self.chosen_function_values = plot_values(self.interval, &|x| f.value(x));
+        self.approx_values = plot_values(self.interval, &|x| legendre_approx_value(&self.lambdas, x, self.interval));
+        self.derivative_values = plot_values(self.interval, &|x| legendre_derivative_value(&self.lambdas, x, self.interval, 1));
+        self.polynomial = format_polynomial(&get_coefficients(&self.lambdas, self.interval));
+        self.convergence = estimate_convergence(&gauss_lambdas(f, CONVERGENCE_DEGREE, self.interval, CONVERGENCE_OVERSAMPLING * (CONVERGENCE_DEGREE + 1)));
+        self.search = Some(search);
+    }
+
+    fn search_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.label("Approx. Epsilon: ");
+                ui.add(egui::Slider::new(&mut self.given_approx_error, 1e-15..=0.1).logarithmic(true));
+                egui::ComboBox::from_id_source("error_norm")
+                    .selected_text(self.error_norm.name())
+                    .show_ui(ui, |ui| {
+                        for norm in ErrorNorm::ALL {
+                            ui.selectable_value(&mut self.error_norm, norm, norm.name());
+                        }
+                    });
+                ui.label("Max. Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.max_degree, 1..=40));
+                ui.label("Integral Nodes");
+                ui.add(egui::Slider::new(&mut self.integral_nodes, 2..=80));
+            });
+            self.quadrature_ui(ui);
+            if ui.button("Calculate").clicked() {
+                // the lambdas of the highest degree tried have to be resolved by the rule
+                if self.integral_nodes <= self.max_degree {
+                    self.integral_nodes = self.max_degree + 1;
+                }
+                self.calculate_search();
+            }
+            ui.group(|ui| {
+                if let Some(search) = &self.search {
+                    if !search.converged {
+                        ui.label("The search criteria were not met. Displaying the best approximation found.");
+                    }
+                    ui.label(format!("Degree: {}", search.degree));
+                }
+                error_ui(ui, "", &self.approx_error);
+                ui.label(self.quadrature_used.as_str());
+                if let Some(search) = &self.search {
+                    egui::CollapsingHeader::new("Degrees Tried").show(ui, |ui| {
+                        for (degree, norms) in search.tried.iter() {
+                            ui.label(format!("{}: {} error {:.3e}", degree, search.norm.name(), search.norm.of(norms)));
+                        }
+                    });
+                }
+                self.lambdas_ui(ui);
+                self.convergence_ui(ui);
+                self.norm_diagnostics_ui(ui);
+            });
+        });
+    }
+
+    /// Computes lambdas up to decay_degree and keeps them up to the start of their plateau
+    fn calculate_decay(&mut self) {
+        let f = match target_function(&self.registry, &self.expression, &self.data, self.target) {
+            Some(f) => f,
+            None => return,
+        };
+        let interval = self.interval;
+        self.extra_lines = Vec::new();
+        self.extra_labels = Vec::new();
+        self.markers = vec![interval.a, interval.b];
+        self.norm_discrepancies = Vec::new();
+
+        let nodes = CONVERGENCE_OVERSAMPLING * (self.decay_degree + 1);
+        let cutoff = truncate_by_decay(f, self.decay_degree, interval, self.decay_tolerance, nodes);
+        self.quadrature_used = format!("Gauss-Legendre ({} nodes)", nodes);
+        self.lambdas = cutoff.lambdas.clone();
+        self.lambda_errors = vec![None; cutoff.lambdas.len()];
+        self.chosen_function_values = plot_values(interval, &|x| f.value(x));
+        self.approx_values = plot_values(interval, &|x| legendre_approx_value(&cutoff.lambdas, x, interval));
+        self.derivative_values = plot_values(interval, &|x| legendre_derivative_value(&cutoff.lambdas, x, interval, 1));
+        self.approx_error = error_norms(f, &|x| legendre_approx_value(&cutoff.lambdas, x, interval), interval, &[], &norm_quadrature());
+        self.coefficient_values = cutoff.coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, lambda)| **lambda != 0.)
+            .map(|(k, lambda)| Value::new(k as f64, lambda.abs().log10()))
+            .collect();
+        // the monomial coefficients of high degrees are meaningless in floating point
+        self.polynomial = format!("Legendre series of degree {}", cutoff.degree());
+        // the cutoff and the convergence estimate are made on the same spectrum
+        self.convergence = estimate_convergence(&cutoff.coefficients);
+        self.cutoff = Some(cutoff);
+    }
+
+    fn decay_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.label("Max. Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.decay_degree, 16..=400));
+                ui.label("Noise Floor (Relative)");
+                ui.add(egui::Slider::new(&mut self.decay_tolerance, 1e-16..=1e-2).logarithmic(true));
+            });
+            if ui.button("Calculate").clicked() {
+                self.calculate_decay();
+            }
+            if let Some((resolved, degree)) = self.cutoff.as_ref().map(|cutoff| (cutoff.resolved, cutoff.degree())) {
+                ui.group(|ui| {
+                    if !resolved {
+                        ui.label("The lambdas didn't reach a plateau, try a higher degree. Displaying all of them.");
+                    }
+                    ui.label(format!("Cutoff Degree: {}", degree));
+                    error_ui(ui, "", &self.approx_error);
+                    ui.label(self.quadrature_used.as_str());
+                    self.lambdas_ui(ui);
+                    self.convergence_ui(ui);
+                });
+            }
+        });
+    }
+
+    fn chebyshev_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.interval_ui(ui);
+            ui.group(|ui| {
+                ui.label("Polynomial Degree");
+                ui.add(egui::Slider::new(&mut self.no_of_nodes, 2..=10));
+                ui.label("Integral Nodes (Legendre)");
+                ui.add(egui::Slider::new(&mut self.integral_nodes, 2..=40));
+            });
+            self.quadrature_ui(ui);
+            if ui.button("Calculate").clicked() {
+                if self.integral_nodes <= self.no_of_nodes {
+                    self.integral_nodes = self.no_of_nodes + 1;
+                }
+                self.calculate_chebyshev();
+            }
+            ui.group(|ui| {
+                error_ui(ui, "Legendre ", &self.approx_error);
+                error_ui(ui, "Chebyshev ", &self.chebyshev_error);
+            });
+        });
+    }
+
+    /// Lets the user choose the integration rule for the lambdas
+    fn quadrature_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label("Quadrature");
+            egui::ComboBox::from_id_source("quadrature")
+                .selected_text(self.quadrature.name())
+                .show_ui(ui, |ui| {
+                    for kind in QuadratureKind::ALL {
+                        ui.selectable_value(&mut self.quadrature, kind, kind.name());
+                    }
+                });
+            if self.quadrature == QuadratureKind::NewtonCotes {
+                ui.label("Newton-Cotes Order");
+                ui.add(egui::Slider::new(&mut self.newton_cotes_order, 1..=8));
+            }
+            if self.quadrature.is_adaptive() {
+                ui.label("Tolerance");
+                ui.add(egui::Slider::new(&mut self.quadrature_tolerance, 1e-14..=1e-2).logarithmic(true));
+            }
+            ui.checkbox(&mut self.analytic_norm, "Analytic Norm 2 / (2k + 1)");
+            ui.checkbox(&mut self.norm_diagnostics, "Norm Diagnostics");
+        });
+    }
+
+    /// Shows how fast the lambdas decay, where the nearest singularity is and the degree needed for prediction_tolerance
+    fn convergence_ui(&mut self, ui: &mut egui::Ui) {
+        let interval = self.interval;
+        egui::CollapsingHeader::new("Convergence").show(ui, |ui| {
+            let estimate = match &self.convergence {
+                Some(estimate) => estimate,
+                None => {
+                    ui.label("Too few lambdas above rounding, f is a polynomial of low degree.");
+                    return;
+                },
+            };
+            match estimate.decay {
+                Decay::Geometric { rho, power } => {
+                    ui.label(format!("Geometric: |λk| ~ k^{:.2} {:.4}^(-k)", power, rho));
+                    if let (Some((left, right)), Some(height)) = (estimate.singularity_bounds(interval), estimate.singularity_height(interval)) {
+                        ui.label(format!("Nearest singularity on the ellipse through x = {:.4} and x = {:.4}, height ±{:.4}", left, right, height));
+                    }
+                },
+                Decay::SuperGeometric { rho } => {
+                    ui.label(format!("Faster than geometric: |λk| < {:.4}^(-k) and steepening", rho));
+                    ui.label("No singularity detected");
+                },
+                Decay::Algebraic { order } => {
+                    ui.label(format!("Algebraic: |λk| ~ k^(-{:.3})", order));
+                    ui.label("Singularity on the interval");
+                },
+            }
+            ui.label(format!("Fitted on degrees {} to {}, residual {:.3}", estimate.fitted.0, estimate.fitted.1, estimate.residual));
+            ui.label("Relative Tolerance");
+            ui.add(egui::Slider::new(&mut self.prediction_tolerance, 1e-15..=1e-1).logarithmic(true));
+            match estimate.predicted_degree(self.prediction_tolerance) {
+                None => ui.label("Predicted Degree: no prediction, the lambdas don't decay"),
+                Some(degree) if degree > 100_000 => ui.label("Predicted Degree: more than 100000"),
+                Some(degree) => ui.label(format!("Predicted Degree: {}", degree)),
+            };
+        });
+    }
+
+    /// Lists the lambdas with the error estimates of the quadrature, if there are any
+    fn lambdas_ui(&self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Lambdas").show(ui, |ui| {
+            for (i, (lambda, error)) in self.lambdas.iter().zip(self.lambda_errors.iter()).enumerate() {
+                match error {
+                    Some(error) => ui.label(format!("λ{} = {:.6} ± {:.1e}", i, lambda, error)),
+                    None => ui.label(format!("λ{} = {:.6}", i, lambda)),
+                };
+            }
+        });
+    }
+
+    /// Compares the numerically integrated norms of L_k with the exact ones
+    fn norm_diagnostics_ui(&self, ui: &mut egui::Ui) {
+        if self.norm_discrepancies.is_empty() {
+            return;
+        }
+        egui::CollapsingHeader::new("Norm Diagnostics").show(ui, |ui| {
+            for (k, (numeric, analytic)) in self.norm_discrepancies.iter().enumerate() {
+                ui.label(format!("k = {}: numeric {:.10}, exact {:.10}, rel. diff {:.2e}", k, numeric, analytic, (numeric - analytic) / analytic));
+            }
+        });
+    }
+
+    /// Reads the CSV file, maps the x-range of its samples onto the interval and selects them as the target
+    fn load_csv(&mut self) {
+        let data = match read_csv(self.csv_path.trim(), &self.csv_options) {
+            Ok(data) => data,
+            Err(error) => {
+                self.csv_error = Some(error);
+                return;
+            },
+        };
+        let name = std::path::Path::new(self.csv_path.trim())
+            .file_name()
+            .map_or(String::from("Data"), |name| name.to_string_lossy().to_string());
+        let function = SampledFunction::new(&name, data.samples);
+        let range = function.range();
+        if range.width() <= 0. {
+            self.csv_error = Some(CsvError { message: String::from("all samples have the same x"), line: 0 });
+            return;
+        }
+        self.csv_error = None;
+        self.interval = range;
+        self.data_headers = data.headers;
+        self.data = Some(function);
+        self.target = Target::Data;
+        self.samples = Vec::new();
+    }
+
+    fn csv_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Load CSV").show(ui, |ui| {
+            ui.label("File");
+            ui.text_edit_singleline(&mut self.csv_path);
+            egui::ComboBox::from_id_source("delimiter")
+                .selected_text(self.csv_options.delimiter.name())
+                .show_ui(ui, |ui| {
+                    for delimiter in Delimiter::ALL {
+                        ui.selectable_value(&mut self.csv_options.delimiter, delimiter, delimiter.name());
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.label("Header");
+                ui.radio_value(&mut self.csv_options.header, Header::Detect, "Detect");
+                ui.radio_value(&mut self.csv_options.header, Header::Present, "Yes");
+                ui.radio_value(&mut self.csv_options.header, Header::Absent, "No");
+            });
+            ui.horizontal(|ui| {
+                ui.label("x Column");
+                ui.add(egui::DragValue::new(&mut self.csv_options.x_column));
+                ui.label("y Column");
+                ui.add(egui::DragValue::new(&mut self.csv_options.y_column));
+            });
+            ui.horizontal(|ui| {
+                let mut weighted = self.csv_options.weight_column.is_some();
+                ui.checkbox(&mut weighted, "Weight Column");
+                match (weighted, self.csv_options.weight_column) {
+                    (true, None) => self.csv_options.weight_column = Some(2),
+                    (false, Some(_)) => self.csv_options.weight_column = None,
+                    _ => {},
+                }
+                if let Some(column) = &mut self.csv_options.weight_column {
+                    ui.add(egui::DragValue::new(column));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Comment");
+                let mut comment = self.csv_options.comment.to_string();
+                if ui.add(egui::TextEdit::singleline(&mut comment).desired_width(20.)).changed() {
+                    if let Some(c) = comment.chars().last() {
+                        self.csv_options.comment = c;
+                    }
+                }
+            });
+            if ui.button("Load").clicked() {
+                self.load_csv();
+            }
+            if let Some(error) = &self.csv_error {
+                ui.colored_label(egui::Color32::RED, error.to_string());
+            }
+            if !self.data_headers.is_empty() {
+                ui.label(format!("Columns: {}", self.data_headers.join(", ")));
+            }
+        });
+    }
+
+    /// Lets the user edit the interval [a, b], keeping a < b
+    fn interval_ui(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.label(format!("Approximation takes place on the interval [{:.3}, {:.3}]", self.interval.a, self.interval.b));
+            interval_ends_ui(ui, &mut self.interval, ["a:", "b:"]);
+        });
+    }
 }
 
 impl App for AppState {
@@ -79,14 +1345,24 @@ impl App for AppState {
                     ui.add_space(5.);
     
                     ui.with_layout(Layout::top_down(egui::Align::LEFT), |ui| {
-                        ui.radio_value(&mut self.function, Function::Poly1, "Polynomial 2nd Power");
-                        ui.radio_value(&mut self.function, Function::Poly2, "Polynomial 4th power");
-                        ui.radio_value(&mut self.function, Function::PerfectFit, "Perfect Fit");
-                        ui.radio_value(&mut self.function, Function::Linear, "Linear");
-                        ui.radio_value(&mut self.function, Function::Sinusoidal, "Sinusoidal");
-                        ui.radio_value(&mut self.function, Function::Absolute, "Absolute");
-                        ui.radio_value(&mut self.function, Function::Mixed, "Mixed");
+                        for (i, function) in self.registry.iter().enumerate() {
+                            ui.radio_value(&mut self.target, Target::Registered(i), function.name());
+                        }
+                        ui.radio_value(&mut self.target, Target::Expression, "Custom Expression");
+                        if let Some(data) = &self.data {
+                            ui.radio_value(&mut self.target, Target::Data, format!("Data: {} ({} samples)", data.name(), data.samples().len()));
+                        }
                     });
+                    if self.target == Target::Expression {
+                        ui.label("f(x) =");
+                        if ui.text_edit_singleline(&mut self.expression_source).changed() {
+                            self.expression = Expression::parse(&self.expression_source);
+                        }
+                        if let Err(error) = &self.expression {
+                            ui.colored_label(egui::Color32::RED, error.to_string());
+                        }
+                    }
+                    self.csv_ui(ui);
                 });
 
                 // ##################################
@@ -98,145 +1374,77 @@ impl App for AppState {
                 if ui.button("Approx. Error").clicked() {
                     self.mode = Mode::AproxError;
                 }
+                if ui.button("Chebyshev").clicked() {
+                    self.mode = Mode::Chebyshev;
+                }
+                if ui.button("Laguerre").clicked() {
+                    self.mode = Mode::Laguerre;
+                }
+                if ui.button("Hermite").clicked() {
+                    self.mode = Mode::Hermite;
+                }
+                if ui.button("Jacobi / Gegenbauer").clicked() {
+                    self.mode = Mode::Orthogonal;
+                }
+                if ui.button("Minimax").clicked() {
+                    self.mode = Mode::Minimax;
+                }
+                if ui.button("Rational").clicked() {
+                    self.mode = Mode::Rational;
+                }
+                if ui.button("Data Fit").clicked() {
+                    self.mode = Mode::DataFit;
+                }
+                if ui.button("Piecewise").clicked() {
+                    self.mode = Mode::Piecewise;
+                }
+                if ui.button("Surface").clicked() {
+                    self.mode = Mode::Surface;
+                }
+                if ui.button("Coefficient Decay").clicked() {
+                    self.mode = Mode::Decay;
+                }
                 match self.mode {
                     Mode::Nodes => {
                         ui.group(|ui| {
-                            ui.group(|ui| {
-                                ui.label("Approximation takes place on the interval [-1, 1]");
-                            }); 
+                            self.interval_ui(ui);
                             //ui.label("Mode");
                             //ui.radio_value(&mut self.mode, Mode::Nodes, "Nodes");
                             //ui.radio_value(&mut self.mode, Mode::AproxError, "Approx. Error");
                             ui.group(|ui| {
                                 ui.label("Polynomial Degree");
                                 ui.add(egui::Slider::new(&mut self.no_of_nodes, 2..=10));
-                                ui.label("Newton-Cotes Nodes");
+                                ui.label("Integral Nodes");
                                 ui.add(egui::Slider::new(&mut self.integral_nodes, 2..=40));
                             });
+                            self.quadrature_ui(ui);
                             if ui.button("Calculate").clicked() {
-                                if self.integral_nodes < self.no_of_nodes {
-                                    self.integral_nodes = self.no_of_nodes;
-                                }
-                                // default some parameters
-                                self.chosen_function_values = Vec::new();
-                                self.approx_values = Vec::new();
-                                self.lambdas = Vec::new();
-            
-                                let min = -1.;
-                                let max = 1.;
-                                // generating values of chosen function for the plot
-                                self.chosen_function_values = (0..10000)
-                                .map(|i| {
-                                    let x = min + (i as f64 *
-                                    ((max) - (min)) / 10000.);
-                                    Value::new(x, function_value(x, self.function))
-                                })
-                                .collect();
-            
-                                // generating values of approximated function for the plot
-                                self.lambdas = calculate_lambdas(self.function, self.no_of_nodes, self.integral_nodes);
-                                self.approx_values = (0..10000)
-                                .map(|i| {
-                                    let x = min + (i as f64 *
-                                    ((max) - (min)) / 10000.);
-                                    Value::new(x, legendre_approx_value(&self.lambdas, x))
-                                })
-                                .collect();
-            
-                                let mut sum = 0.;
-                                let step = (max - min) / self.no_of_nodes as f64;
-                                for i in 0..self.no_of_nodes {
-                                    sum += (function_value(min + i as f64 * step, self.function) - legendre_approx_value(&self.lambdas, min + i as f64 * step)).powi(2);
+                                // Gauss-Legendre with no_of_nodes nodes would sample the last L_k only in its roots
+                                if self.integral_nodes <= self.no_of_nodes {
+                                    self.integral_nodes = self.no_of_nodes + 1;
                                 }
-                                self.approx_error = sum.sqrt();
-                                
-                                let mut polynomial: String = String::from(" ");
-                                let poly = get_coefficients(&self.lambdas);
-                                for (i, j) in poly.iter().enumerate() {
-                                    if i == self.no_of_nodes  {
-                                        polynomial += format!("{:.3}x^{}" , j, self.no_of_nodes - i).as_str();
-                                    } else {
-                                        polynomial += format!("{:.3}x^{} + " , j, self.no_of_nodes - i).as_str();
-                                    }
-                                    
-                                }
-                                self.polynomial = polynomial;
+                                self.calculate();
                             }
-                            let error = format!("Approx. Error: {:.6}", self.approx_error);
                             ui.group(|ui| {
-                                ui.label(error);
-                            });
-                        });
-                    },
-                    Mode::AproxError => {
-                        ui.group(|ui| {
-                            self.integral_nodes = 40;
-                            ui.group(|ui| {
-                                ui.label("Approximation takes place on the interval [-1, 1]");
-                            }); 
-                            ui.group(|ui| {
-                                ui.label("Approx. Epsilon: ");
-                                ui.add(egui::Slider::new(&mut self.given_approx_error, 1e-15..=0.1).logarithmic(true));
-                            });
-                            if ui.button("Calculate").clicked() {
-                                // default some parameters
-                                self.chosen_function_values = Vec::new();
-                                self.approx_values = Vec::new();
-                                self.lambdas = Vec::new();
-            
-                                let (best_deg, flag) = best_approximation(self.function, self.given_approx_error);
-                                self.no_of_nodes = best_deg;
-                                self.epsilon_flag = flag;
-
-                                let min = -1.;
-                                let max = 1.;
-                                // generating values of chosen function for the plot
-                                self.chosen_function_values = (0..10000)
-                                .map(|i| {
-                                    let x = min + (i as f64 *
-                                    ((max) - (min)) / 10000.);
-                                    Value::new(x, function_value(x, self.function))
-                                })
-                                .collect();
-            
-                                // generating values of approximated function for the plot
-                                self.lambdas = calculate_lambdas(self.function, self.no_of_nodes, self.integral_nodes);
-                                self.approx_values = (0..10000)
-                                .map(|i| {
-                                    let x = min + (i as f64 *
-                                    ((max) - (min)) / 10000.);
-                                    Value::new(x, legendre_approx_value(&self.lambdas, x))
-                                })
-                                .collect();
-            
-                                let mut sum = 0.;
-                                let step = (max - min) / self.no_of_nodes as f64;
-                                for i in 0..self.no_of_nodes {
-                                    sum += (function_value(min + i as f64 * step, self.function) - legendre_approx_value(&self.lambdas, min + i as f64 * step)).powi(2);
-                                }
-                                self.approx_error = sum.sqrt();
-                                
-                                let mut polynomial: String = String::from(" ");
-                                let poly = get_coefficients(&self.lambdas);
-                                for (i, j) in poly.iter().enumerate() {
-                                    if i == self.no_of_nodes  {
-                                        polynomial += format!("{:.3}x^{}" , j, self.no_of_nodes - i).as_str();
-                                    } else {
-                                        polynomial += format!("{:.3}x^{} + " , j, self.no_of_nodes - i).as_str();
-                                    }
-                                    
-                                }
-                                self.polynomial = polynomial;
-                            }
-                            let error = format!("Approx. Error: {:.6}", self.approx_error);
-                            ui.group(|ui| {
-                                if !self.epsilon_flag {
-                                    ui.label("The search criteria were not met. Displaying the best approximation found.");
-                                }
-                                ui.label(error);
+                                error_ui(ui, "", &self.approx_error);
+                                ui.label(self.quadrature_used.as_str());
+                                self.lambdas_ui(ui);
+                                self.convergence_ui(ui);
+                                self.norm_diagnostics_ui(ui);
                             });
                         });
                     },
+                    Mode::AproxError => self.search_ui(ui),
+                    Mode::Chebyshev => self.chebyshev_ui(ui),
+                    Mode::Laguerre => self.laguerre_ui(ui),
+                    Mode::Hermite => self.hermite_ui(ui),
+                    Mode::Orthogonal => self.orthogonal_ui(ui),
+                    Mode::Minimax => self.minimax_ui(ui),
+                    Mode::Rational => self.rational_ui(ui),
+                    Mode::DataFit => self.data_fit_ui(ui),
+                    Mode::Piecewise => self.piecewise_ui(ui),
+                    Mode::Surface => self.surface_ui(ui),
+                    Mode::Decay => self.decay_ui(ui),
                 }
                 
                 
@@ -244,16 +1452,20 @@ impl App for AppState {
         });
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
+                if self.mode == Mode::Surface {
+                    self.surface_view(ui);
+                    return;
+                }
                 //chosen function
                 let chosen_values = Values::from_values(self.chosen_function_values.clone());
                 let approximated_values = Values::from_values(self.approx_values.clone());
                 let chosen_plot = Line::new(chosen_values).name("Chosen Function");
                 let approx_plot = Line::new(approximated_values).name("Approx. Function");
 
-                let vline_left = VLine::new(-1.);
-                let vline_right = VLine::new(1.);
-
-                ui.checkbox(&mut self.center_plot, "Center Plot");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.center_plot, "Center Plot");
+                    ui.checkbox(&mut self.show_derivative, "Show Derivative");
+                });
                 let mut plot = Plot::new("my_plot")
                     .show_x(true)
                     .show_y(true)
@@ -265,12 +1477,50 @@ impl App for AppState {
                 plot.show(ui, |plot_ui| {
                     plot_ui.line(chosen_plot);
                     plot_ui.line(approx_plot);
-                    plot_ui.vline(vline_left);
-                    plot_ui.vline(vline_right);
+                    if self.show_derivative {
+                        let derivative_values = Values::from_values(self.derivative_values.clone());
+                        plot_ui.line(Line::new(derivative_values).name("Approx. Derivative"));
+                    }
+                    for (name, values) in self.extra_lines.iter() {
+                        plot_ui.line(Line::new(Values::from_values(values.clone())).name(name));
+                    }
+                    for marker in self.markers.iter() {
+                        plot_ui.vline(VLine::new(*marker));
+                    }
+                    if self.mode == Mode::DataFit {
+                        plot_ui.points(Points::new(Values::from_values(self.sample_points.clone())).radius(2.).name("Samples"));
+                    }
                 });
+                if let (Mode::Minimax, Some(minimax)) = (self.mode, &self.minimax) {
+                    // error curve with the alternation points and the levels +-E
+                    Plot::new("error_plot")
+                        .height(250.)
+                        .legend(egui::widgets::plot::Legend::default())
+                        .show(ui, |plot_ui| {
+                            plot_ui.line(Line::new(Values::from_values(self.error_values.clone())).name("Error f - p"));
+                            plot_ui.points(Points::new(Values::from_values(self.alternation_values.clone())).radius(4.).name("Alternation Points"));
+                            plot_ui.hline(HLine::new(minimax.levelled_error).name("+E"));
+                            plot_ui.hline(HLine::new(-minimax.levelled_error).name("-E"));
+                        });
+                }
+                if let (Mode::Decay, Some(cutoff)) = (self.mode, &self.cutoff) {
+                    // the plot has no logarithmic axis, so the values are log10 |lambda_k| already
+                    Plot::new("coefficient_plot")
+                        .height(250.)
+                        .legend(egui::widgets::plot::Legend::default())
+                        .show(ui, |plot_ui| {
+                            plot_ui.points(Points::new(Values::from_values(self.coefficient_values.clone())).radius(2.).name("log10 |λk|"));
+                            plot_ui.vline(VLine::new(cutoff.degree() as f64).name("Cutoff"));
+                            let scale = cutoff.coefficients.iter().fold(0., |max: f64, c| max.max(c.abs()));
+                            plot_ui.hline(HLine::new((self.decay_tolerance * scale).log10()).name("Noise Floor"));
+                        });
+                }
                 ui.group(|ui| {
                     ui.add_space(5.);
                     ui.label(self.polynomial.as_str());
+                    for label in self.extra_labels.iter() {
+                        ui.label(label.as_str());
+                    }
                     ui.add_space(5.);
                 });
             }); 
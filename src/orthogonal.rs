@@ -0,0 +1,250 @@
+use std::f64::consts::PI;
+
+use crate::{Interval, functions::{basis_to_monomial, TargetFunction}, gauss::golub_welsch};
+
+/// Family of polynomials orthogonal on [-1, 1] with some weight function,
+/// described by its three-term recurrence
+/// ### P_{n+1}(t) = (a_n t + b_n) P_n(t) - c_n P_{n-1}(t), P_0 = 1
+/// Everything else (values, monomial coefficients, the Gauss rule and the projection) follows from it.
+pub trait OrthogonalFamily {
+    /// Name shown in the GUI
+    fn name(&self) -> String;
+
+    /// Weight function w(t) of the inner product
+    fn weight(&self, t: f64) -> f64;
+
+    /// Coefficients (a_n, b_n, c_n) of the recurrence, c_0 is never used
+    fn recurrence(&self, n: usize) -> (f64, f64, f64);
+
+    /// Squared norm of P_n, integral of w(t) * P_n(t)^2 over [-1, 1]
+    fn norm(&self, n: usize) -> f64;
+
+    /// Interval of orthogonality, every family here lives on [-1, 1]
+    fn interval(&self) -> Interval {
+        Interval::new(-1., 1.)
+    }
+
+    /// Returns P_n(t) computed with the recurrence
+    fn value(&self, n: usize, t: f64) -> f64 {
+        let mut p1 = 1.;
+        let mut p2 = 0.;
+        for k in 0..n {
+            let (a, b, c) = self.recurrence(k);
+            let p3 = p2;
+            p2 = p1;
+            p1 = (a * t + b) * p2 - c * p3;
+        }
+        p1
+    }
+
+    /// Returns the coefficients of P_n in t, highest power first
+    fn polynomial(&self, n: usize) -> Vec<f64> {
+        // lowest power first while building
+        let mut previous: Vec<f64> = Vec::new();
+        let mut current: Vec<f64> = vec![1.];
+        for k in 0..n {
+            let (a, b, c) = self.recurrence(k);
+            let mut next = vec![0.; k + 2];
+            for (i, elem) in current.iter().enumerate() {
+                next[i + 1] += a * elem;
+                next[i] += b * elem;
+            }
+            for (i, elem) in previous.iter().enumerate() {
+                next[i] -= c * elem;
+            }
+            previous = current;
+            current = next;
+        }
+        current.reverse();
+        current
+    }
+
+    /// Nodes and weights of the Gauss rule with n nodes for the weight function of the family
+    fn gauss_rule(&self, n: usize) -> (Vec<f64>, Vec<f64>) {
+        // monic recurrence p_{k+1} = (t - alpha_k) p_k - beta_k p_{k-1}, with p_k = P_k / (a_0 * .. * a_{k-1})
+        let mut alpha: Vec<f64> = Vec::new();
+        let mut beta: Vec<f64> = Vec::new();
+        for k in 0..n {
+            let (a, b, c) = self.recurrence(k);
+            alpha.push(-b / a);
+            if k > 0 {
+                beta.push(c / (a * self.recurrence(k - 1).0));
+            }
+        }
+        golub_welsch(&alpha, &beta, self.norm(0))
+    }
+}
+
+/// Jacobi polynomials P_n^(alpha, beta), orthogonal with the weight (1 - t)^alpha (1 + t)^beta, alpha, beta > -1.
+/// Legendre polynomials are alpha = beta = 0, Chebyshev polynomials (up to scaling) alpha = beta = -1/2.
+/// https://en.wikipedia.org/wiki/Jacobi_polynomials
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jacobi {
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Jacobi {
+    pub fn new(alpha: f64, beta: f64) -> Jacobi {
+        Jacobi { alpha, beta }
+    }
+
+    pub fn legendre() -> Jacobi {
+        Jacobi::new(0., 0.)
+    }
+
+    pub fn chebyshev() -> Jacobi {
+        Jacobi::new(-0.5, -0.5)
+    }
+}
+
+impl OrthogonalFamily for Jacobi {
+    fn name(&self) -> String {
+        format!("Jacobi ({}, {})", self.alpha, self.beta)
+    }
+
+    fn weight(&self, t: f64) -> f64 {
+        (1. - t).powf(self.alpha) * (1. + t).powf(self.beta)
+    }
+
+    /// ### 2(n+1)(n+a+b+1)(2n+a+b) P_{n+1} = (2n+a+b+1)((2n+a+b+2)(2n+a+b) t + a^2 - b^2) P_n - 2(n+a)(n+b)(2n+a+b+2) P_{n-1}
+    /// For n = 0 the factor 2n+a+b can vanish, so P_1 = ((a+b+2) t + a - b) / 2 is used directly.
+    fn recurrence(&self, n: usize) -> (f64, f64, f64) {
+        let (a, b) = (self.alpha, self.beta);
+        if n == 0 {
+            return ((a + b + 2.) / 2., (a - b) / 2., 0.);
+        }
+        let n = n as f64;
+        let s = 2. * n + a + b;
+        let d = 2. * (n + 1.) * (n + a + b + 1.) * s;
+        (
+            (s + 1.) * (s + 2.) * s / d,
+            (s + 1.) * (a * a - b * b) / d,
+            2. * (n + a) * (n + b) * (s + 2.) / d,
+        )
+    }
+
+    /// ### h_n = 2^(a+b+1) / (2n+a+b+1) * G(n+a+1) G(n+b+1) / (G(n+a+b+1) n!)
+    /// For n = 0 the first two factors are merged into G(a+b+2) so that a + b = -1 works.
+    fn norm(&self, n: usize) -> f64 {
+        let (a, b) = (self.alpha, self.beta);
+        let log_power = (a + b + 1.) * 2f64.ln();
+        if n == 0 {
+            return (log_power + ln_gamma(a + 1.) + ln_gamma(b + 1.) - ln_gamma(a + b + 2.)).exp();
+        }
+        let n = n as f64;
+        (log_power + ln_gamma(n + a + 1.) + ln_gamma(n + b + 1.) - ln_gamma(n + a + b + 1.) - ln_gamma(n + 1.)).exp()
+            / (2. * n + a + b + 1.)
+    }
+}
+
+/// Gegenbauer (ultraspherical) polynomials C_n^(lambda), orthogonal with the weight (1 - t^2)^(lambda - 1/2), lambda > -1/2, lambda != 0.
+/// Legendre polynomials are lambda = 1/2, Chebyshev polynomials of the second kind lambda = 1.
+/// https://en.wikipedia.org/wiki/Gegenbauer_polynomials
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gegenbauer {
+    pub lambda: f64,
+}
+
+impl Gegenbauer {
+    pub fn new(lambda: f64) -> Gegenbauer {
+        Gegenbauer { lambda }
+    }
+}
+
+impl OrthogonalFamily for Gegenbauer {
+    fn name(&self) -> String {
+        format!("Gegenbauer ({})", self.lambda)
+    }
+
+    fn weight(&self, t: f64) -> f64 {
+        (1. - t * t).powf(self.lambda - 0.5)
+    }
+
+    /// ### (n+1) C_{n+1} = 2(n+l) t C_n - (n+2l-1) C_{n-1}
+    fn recurrence(&self, n: usize) -> (f64, f64, f64) {
+        let l = self.lambda;
+        let n = n as f64;
+        (2. * (n + l) / (n + 1.), 0., (n + 2. * l - 1.) / (n + 1.))
+    }
+
+    /// ### h_n = pi 2^(1-2l) G(n+2l) / (n! (n+l) G(l)^2)
+    /// For negative l the signs of G(2l) and n + l cancel at n = 0, so absolute values are used throughout.
+    fn norm(&self, n: usize) -> f64 {
+        let l = self.lambda;
+        let n = n as f64;
+        (PI.ln() + (1. - 2. * l) * 2f64.ln() + ln_gamma(n + 2. * l) - ln_gamma(n + 1.) - 2. * ln_gamma(l)).exp()
+            / (n + l).abs()
+    }
+}
+
+/// Logarithm of the absolute value of the gamma function, Lanczos approximation (g = 7, 9 terms)
+/// https://en.wikipedia.org/wiki/Lanczos_approximation
+pub fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.;
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // reflection formula G(x) G(1 - x) = pi / sin(pi x)
+        return (PI / (PI * x).sin().abs()).ln() - ln_gamma(1. - x);
+    }
+    let x = x - 1.;
+    let mut sum = COEFFS[0];
+    for (i, c) in COEFFS.iter().enumerate().skip(1) {
+        sum += c / (x + i as f64);
+    }
+    let t = x + G + 0.5;
+    0.5 * (2. * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// calculates the coefficients of the projection of f onto P_0..P_deg of the family, with x = x(t) mapped onto the interval
+/// ### c_k = integral of w(t) * f(x(t)) * P_k(t) over [-1, 1] / h_k
+/// The integrals use the Gauss rule of the family with `nodes` nodes, so the weight and its
+/// endpoint singularities are integrated exactly.
+pub fn family_coefficients(family: &dyn OrthogonalFamily, f: &dyn TargetFunction, deg: usize, nodes: usize, interval: Interval) -> Vec<f64> {
+    let (t, w) = family.gauss_rule(nodes);
+    let values: Vec<f64> = t.iter().map(|t| f.value(interval.from_unit(*t))).collect();
+    (0..(deg+1))
+    .map(|k| {
+        let mut sum = 0.;
+        for i in 0..t.len() {
+            sum += w[i] * values[i] * family.value(k, t[i]);
+        }
+        sum / family.norm(k)
+    })
+    .collect()
+}
+
+/// Returns the value of the series sum(c_k * P_k(t)) using Clenshaw's recurrence
+/// ### b_k = c_k + (a_k t + b_k) * b_{k+1} - c_{k+1} * b_{k+2}, value = b_0
+pub fn family_clenshaw(family: &dyn OrthogonalFamily, coeffs: &[f64], t: f64) -> f64 {
+    let mut b1 = 0.;
+    let mut b2 = 0.;
+    for k in (0..coeffs.len()).rev() {
+        let (a, b, _) = family.recurrence(k);
+        let (_, _, c) = family.recurrence(k + 1);
+        let b0 = coeffs[k] + (a * t + b) * b1 - c * b2;
+        b2 = b1;
+        b1 = b0;
+    }
+    b1
+}
+
+/// Returns the value of the approximation in point x from [a, b]
+pub fn family_approx_value(family: &dyn OrthogonalFamily, coeffs: &[f64], x: f64, interval: Interval) -> f64 {
+    family_clenshaw(family, coeffs, interval.to_unit(x))
+}
+
+/// Returns the coefficients of the approximation in the original variable x, highest power first
+pub fn get_family_coefficients(family: &dyn OrthogonalFamily, coeffs: &[f64], interval: Interval) -> Vec<f64> {
+    basis_to_monomial(coeffs, |k| family.polynomial(k), Some(interval))
+}
@@ -0,0 +1,210 @@
+use crate::{Interval, functions::TargetFunction, integral::Quadrature, legendre::{calculate_lambdas, legendre_approx_value}};
+
+/// Elements are never bisected more often than this, which bounds their width from below by (b - a) / 2^MAX_DEPTH
+const MAX_DEPTH: usize = 14;
+
+/// Legendre approximation on one subinterval
+#[derive(Debug, Clone)]
+pub struct Element {
+    pub interval: Interval,
+    pub lambdas: Vec<f64>,
+}
+
+/// Approximation made of polynomials on consecutive subintervals (spectral elements)
+#[derive(Debug, Clone)]
+pub struct PiecewiseApproximation {
+    pub elements: Vec<Element>,
+}
+
+impl PiecewiseApproximation {
+    /// Value in point x, points outside the elements are extrapolated by the first or last one
+    pub fn value(&self, x: f64) -> f64 {
+        let i = self.elements.partition_point(|element| element.interval.b < x).min(self.elements.len() - 1);
+        let element = &self.elements[i];
+        legendre_approx_value(&element.lambdas, x, element.interval)
+    }
+
+    /// Ends of all elements, from a to b
+    pub fn boundaries(&self) -> Vec<f64> {
+        let mut out: Vec<f64> = self.elements.iter().map(|element| element.interval.a).collect();
+        if let Some(last) = self.elements.last() {
+            out.push(last.interval.b);
+        }
+        out
+    }
+
+    /// Largest difference of the neighbouring polynomials at the interior boundaries
+    pub fn max_jump(&self) -> f64 {
+        self.elements
+            .windows(2)
+            .map(|pair| {
+                let x = pair[0].interval.b;
+                (legendre_approx_value(&pair[0].lambdas, x, pair[0].interval) - legendre_approx_value(&pair[1].lambdas, x, pair[1].interval)).abs()
+            })
+            .fold(0., f64::max)
+    }
+}
+
+/// Returns the elements - 1 interior points splitting the interval into equally wide elements
+pub fn uniform_breakpoints(interval: Interval, elements: usize) -> Vec<f64> {
+    (1..elements).map(|i| interval.a + interval.width() * i as f64 / elements as f64).collect()
+}
+
+/// Approximates f by a polynomial of degree poly_deg on every element between the breakpoints.
+/// Every element gets its own lambdas from `calculate_lambdas` on its subinterval.
+/// Breakpoints outside (a, b) are ignored.
+///
+/// With `continuous` the polynomials are corrected to meet in the average of their values at every
+/// interior breakpoint. The correction of an element changes both of its end values at once, L_k(-1) = (-1)^k
+/// and L_k(1) = 1, and is the one with the smallest L2 norm, which makes it
+/// ### delta_k = (2k + 1) * (mu_1 (-1)^k + mu_2)
+/// with the 2x2 system for the multipliers mu coming from the two end conditions.
+/// A constant can't match two neighbours, so degree 0 stays discontinuous.
+/// * quadrature, analytic_norm - see `calculate_lambdas`
+pub fn piecewise_approximation(f: &dyn TargetFunction, poly_deg: usize, interval: Interval, breakpoints: &[f64], quadrature: &dyn Quadrature, analytic_norm: bool, continuous: bool) -> PiecewiseApproximation {
+    let mut points: Vec<f64> = breakpoints.iter().copied().filter(|x| *x > interval.a && *x < interval.b).collect();
+    points.sort_by(|a, b| a.total_cmp(b));
+    points.dedup();
+    points.insert(0, interval.a);
+    points.push(interval.b);
+
+    let mut elements: Vec<Element> = points
+        .windows(2)
+        .map(|pair| {
+            let element = Interval::new(pair[0], pair[1]);
+            Element { interval: element, lambdas: calculate_lambdas(f, poly_deg, element, quadrature, analytic_norm) }
+        })
+        .collect();
+
+    if continuous && poly_deg > 0 {
+        // end values (t = -1, t = 1) of every element before the correction
+        let ends: Vec<(f64, f64)> = elements
+            .iter()
+            .map(|element| {
+                let left = element.lambdas.iter().enumerate().map(|(k, l)| if k % 2 == 0 { *l } else { -l }).sum();
+                let right = element.lambdas.iter().sum();
+                (left, right)
+            })
+            .collect();
+        for (i, element) in elements.iter_mut().enumerate() {
+            let target_left = match i {
+                0 => ends[i].0,
+                _ => 0.5 * (ends[i - 1].1 + ends[i].0),
+            };
+            let target_right = match ends.get(i + 1) {
+                Some(next) => 0.5 * (ends[i].1 + next.0),
+                None => ends[i].1,
+            };
+            let (delta_left, delta_right) = (target_left - ends[i].0, target_right - ends[i].1);
+
+            // Gram matrix [[s, g], [g, s]] of the two end conditions
+            let s: f64 = (0..=poly_deg).map(|k| (2 * k + 1) as f64).sum();
+            let g: f64 = (0..=poly_deg).map(|k| if k % 2 == 0 { (2 * k + 1) as f64 } else { -((2 * k + 1) as f64) }).sum();
+            let det = s * s - g * g;
+            let mu_left = (s * delta_left - g * delta_right) / det;
+            let mu_right = (s * delta_right - g * delta_left) / det;
+            for (k, lambda) in element.lambdas.iter_mut().enumerate() {
+                let sign = if k % 2 == 0 { 1. } else { -1. };
+                *lambda += (2 * k + 1) as f64 * (mu_left * sign + mu_right);
+            }
+        }
+    }
+    PiecewiseApproximation { elements }
+}
+
+/// Finds breakpoints for `piecewise_approximation` automatically.
+/// An element counts as resolved when the two lambdas following poly_deg (one even and one odd, so symmetric
+/// functions don't pass by accident), which estimate what the element polynomial leaves out, are below
+/// tolerance times its largest lambda. Judging the own lambdas instead keeps smooth elements
+/// from ever passing at low degrees, where those lambdas are the function itself.
+/// Unresolved elements are bisected recursively. Elements still unresolved after 14 bisections
+/// contain a kink or a jump and become a single breakpoint at their middle, their halves go to the neighbours.
+/// Between these breakpoints neighbouring elements are merged greedily from the left as long as the merged
+/// element is still resolved, which keeps the number of pieces small.
+/// Returns the interior breakpoints, sorted.
+/// * poly_deg - degree on every element, at least 3
+/// * quadrature, analytic_norm - see `calculate_lambdas`
+pub fn adaptive_breakpoints(f: &dyn TargetFunction, poly_deg: usize, interval: Interval, tolerance: f64, quadrature: &dyn Quadrature, analytic_norm: bool) -> Vec<f64> {
+    assert!(poly_deg >= 3, "adaptive_breakpoints needs a degree of at least 3");
+    let resolved = |element: Interval| -> bool {
+        let lambdas = calculate_lambdas(f, poly_deg + 2, element, quadrature, analytic_norm);
+        let scale = lambdas.iter().fold(0., |max: f64, l| max.max(l.abs()));
+        let tail = lambdas.iter().rev().take(2).fold(0., |max: f64, l| max.max(l.abs()));
+        tail <= tolerance * scale
+    };
+
+    // bisection, leaves from left to right with whether they are resolved
+    let mut leaves: Vec<(Interval, bool)> = Vec::new();
+    let mut stack: Vec<(Interval, usize)> = vec![(interval, 0)];
+    while let Some((element, depth)) = stack.pop() {
+        let element_resolved = resolved(element);
+        if element_resolved || depth >= MAX_DEPTH {
+            leaves.push((element, element_resolved));
+        } else {
+            let middle = 0.5 * (element.a + element.b);
+            // right half first so the left one is popped next
+            stack.push((Interval::new(middle, element.b), depth + 1));
+            stack.push((Interval::new(element.a, middle), depth + 1));
+        }
+    }
+
+    // greedy merge of the resolved leaves between the cuts at unresolved ones
+    let mut breakpoints: Vec<f64> = Vec::new();
+    let mut current: Option<Interval> = None;
+    let mut i = 0;
+    while i < leaves.len() {
+        let (leaf, leaf_resolved) = leaves[i];
+        if !leaf_resolved {
+            // neighbouring unresolved leaves form one cut
+            let mut end = leaf.b;
+            while i + 1 < leaves.len() && !leaves[i + 1].1 {
+                i += 1;
+                end = leaves[i].0.b;
+            }
+            if leaf.a > interval.a && end < interval.b {
+                breakpoints.push(0.5 * (leaf.a + end));
+            }
+            current = None;
+        } else {
+            current = match current {
+                Some(element) if resolved(Interval::new(element.a, leaf.b)) => Some(Interval::new(element.a, leaf.b)),
+                Some(element) => {
+                    breakpoints.push(element.b);
+                    Some(leaf)
+                },
+                None => Some(leaf),
+            };
+        }
+        i += 1;
+    }
+
+    breakpoints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{functions::FunctionRegistry, gauss::GaussLegendre};
+
+    #[test]
+    fn finds_the_kinks_of_mixed() {
+        let registry = FunctionRegistry::with_builtins();
+        let mixed = registry.iter().find(|f| f.name() == "Mixed").unwrap();
+        let quadrature = GaussLegendre::new(20);
+        for (poly_deg, tolerance) in [(6, 1e-4), (8, 1e-4), (8, 1e-6), (10, 1e-6)] {
+            let breakpoints = adaptive_breakpoints(mixed, poly_deg, Interval::new(-3., 6.), tolerance, &quadrature, true);
+            assert_eq!(breakpoints.len(), 3, "degree {}: {:?}", poly_deg, breakpoints);
+            for (found, kink) in breakpoints.iter().zip([0., 2., 4.]) {
+                assert!((found - kink).abs() < 1e-2, "degree {}: {:?}", poly_deg, breakpoints);
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_functions_need_few_pieces() {
+        let registry = FunctionRegistry::with_builtins();
+        let sinusoidal = registry.iter().find(|f| f.name() == "Sinusoidal").unwrap();
+        let breakpoints = adaptive_breakpoints(sinusoidal, 3, Interval::new(-1., 1.), 1e-4, &GaussLegendre::new(20), true);
+        assert!(breakpoints.len() <= 8, "{:?}", breakpoints);
+    }
+}
@@ -0,0 +1,157 @@
+use std::f64::consts::PI;
+
+use crate::{Interval, functions::TargetFunction, gauss::gauss_legendre, legendre::{clenshaw, get_coefficients, legendre_norm, legendre_values}, linalg::{least_squares, solve}};
+
+/// Smallest |Q_prev| relative to its largest value the weights of `rational_least_squares` divide by
+const DENOMINATOR_FLOOR: f64 = 1e-8;
+
+/// Rational function P(t) / Q(t) with numerator and denominator given as Legendre series in t from [-1, 1].
+/// The constant Legendre coefficient of the denominator is 1.
+#[derive(Debug, Clone)]
+pub struct RationalApproximation {
+    pub numerator: Vec<f64>,
+    pub denominator: Vec<f64>,
+}
+
+impl RationalApproximation {
+    /// Value of the rational function in point x from [a, b]
+    pub fn value(&self, x: f64, interval: Interval) -> f64 {
+        let t = interval.to_unit(x);
+        clenshaw(&self.numerator, t) / clenshaw(&self.denominator, t)
+    }
+
+    /// Complex roots (re, im) of the denominator in the original variable x.
+    /// Poles of the approximant close to the interval usually mirror singularities of f.
+    pub fn poles(&self, interval: Interval) -> Vec<(f64, f64)> {
+        polynomial_roots(&get_coefficients(&self.denominator, interval))
+    }
+}
+
+/// Legendre-Pade approximation with numerator degree m and denominator degree n built from the lambdas of f.
+/// Q = 1 + sum(q_k L_k) is chosen so that f Q - P is orthogonal to L_0..L_{m+n}:
+/// ### sum(q_k <f L_k, L_i>) = -<f, L_i>, i = m+1..m+n
+/// ### p_i = <f Q, L_i> / h_i, i = 0..m
+/// f is replaced by its Legendre series, so the inner products are sums of the triple products
+/// <L_j L_k L_i> weighted by the lambdas. They are integrated exactly with a Gauss-Legendre rule.
+/// Only lambdas up to degree m + 2n take part, fewer truncate the series earlier.
+/// Returns None when the system for the denominator is singular.
+/// https://en.wikipedia.org/wiki/Pad%C3%A9_approximant
+pub fn legendre_pade(lambdas: &[f64], m: usize, n: usize) -> Option<RationalApproximation> {
+    let lambdas = &lambdas[..lambdas.len().min(m + 2 * n + 1)];
+    // the integrands have degree at most (m + 2n) + n + (m + n)
+    let (t, w) = gauss_legendre((2 * m + 4 * n) / 2 + 2);
+    let series: Vec<f64> = t.iter().map(|t| clenshaw(lambdas, *t)).collect();
+    let values: Vec<Vec<f64>> = t.iter().map(|t| legendre_values(m + n, *t)).collect();
+    // <f L_k, L_i>
+    let inner = |k: usize, i: usize| -> f64 {
+        (0..t.len()).map(|s| w[s] * series[s] * values[s][k] * values[s][i]).sum()
+    };
+
+    let mut q = vec![1.];
+    if n > 0 {
+        let matrix: Vec<Vec<f64>> = ((m + 1)..=(m + n)).map(|i| (1..=n).map(|k| inner(k, i)).collect()).collect();
+        let rhs: Vec<f64> = ((m + 1)..=(m + n)).map(|i| -inner(0, i)).collect();
+        q.extend(solve(matrix, rhs)?);
+    }
+    let p: Vec<f64> = (0..=m)
+        .map(|i| q.iter().enumerate().map(|(k, q)| q * inner(k, i)).sum::<f64>() / legendre_norm(i))
+        .collect();
+    Some(RationalApproximation { numerator: p, denominator: q })
+}
+
+/// Rational approximation with numerator degree m and denominator degree n fitted to f in
+/// 10 (m + n + 1) Chebyshev points by linearized least squares:
+/// ### minimize sum((f(t_s) Q(t_s) - P(t_s))^2 / |Q_prev(t_s)|^2)
+/// The first pass has Q_prev = 1, every further iteration (Sanathanan-Koerner) reweights with the last
+/// denominator, which moves the linearized residual towards the true residual f - P / Q.
+/// |Q_prev| is kept above 1e-8 of its largest value, so a denominator with a root at a sample can't poison the weights.
+/// Returns None when the least-squares system is rank deficient or a denominator isn't finite.
+pub fn rational_least_squares(f: &dyn TargetFunction, m: usize, n: usize, interval: Interval, iterations: usize) -> Option<RationalApproximation> {
+    let samples = 10 * (m + n + 1);
+    let t: Vec<f64> = (0..samples).map(|s| (PI * (s as f64 + 0.5) / samples as f64).cos()).collect();
+    let values: Vec<f64> = t.iter().map(|t| f.value(interval.from_unit(*t))).collect();
+    let basis: Vec<Vec<f64>> = t.iter().map(|t| legendre_values(m.max(n), *t)).collect();
+
+    let mut q = vec![1.];
+    let mut p: Vec<f64> = Vec::new();
+    for _ in 0..(iterations + 1) {
+        // a denominator vanishing at a sample would give it an infinite weight, so |Q| is kept above a floor
+        let denominators: Vec<f64> = t.iter().map(|t| clenshaw(&q, *t).abs()).collect();
+        let largest = denominators.iter().fold(0., |max: f64, d| max.max(*d));
+        if !largest.is_finite() || largest == 0. {
+            return None;
+        }
+        let weights: Vec<f64> = denominators.iter().map(|d| 1. / d.max(DENOMINATOR_FLOOR * largest)).collect();
+        // unknowns p_0..p_m, q_1..q_n
+        let matrix: Vec<Vec<f64>> = (0..samples)
+            .map(|s| {
+                let mut row: Vec<f64> = (0..=m).map(|k| weights[s] * basis[s][k]).collect();
+                row.extend((1..=n).map(|k| -weights[s] * values[s] * basis[s][k]));
+                row
+            })
+            .collect();
+        let rhs: Vec<f64> = (0..samples).map(|s| weights[s] * values[s]).collect();
+        let solution = least_squares(matrix, rhs)?;
+        p = solution[..=m].to_vec();
+        q = vec![1.];
+        q.extend_from_slice(&solution[(m + 1)..]);
+    }
+    Some(RationalApproximation { numerator: p, denominator: q })
+}
+
+/// Returns all complex roots (re, im) of a polynomial with the Durand-Kerner iteration.
+/// Leading coefficients negligible against the others are dropped first.
+/// https://en.wikipedia.org/wiki/Durand%E2%80%93Kerner_method
+/// * a - coefficients, highest power first like `horner` expects
+pub fn polynomial_roots(a: &[f64]) -> Vec<(f64, f64)> {
+    let magnitude = a.iter().fold(0., |max: f64, c| max.max(c.abs()));
+    let a: Vec<f64> = a.iter().skip_while(|c| c.abs() <= 1e-12 * magnitude).copied().collect();
+    if a.len() < 2 {
+        return Vec::new();
+    }
+    let deg = a.len() - 1;
+    let monic: Vec<f64> = a.iter().map(|c| c / a[0]).collect();
+    // Cauchy's bound on the size of the roots
+    let radius = 1. + monic.iter().skip(1).fold(0., |max: f64, c| max.max(c.abs()));
+
+    let mut roots: Vec<(f64, f64)> = (0..deg)
+        .map(|k| {
+            let angle = 2. * PI * k as f64 / deg as f64 + 0.4;
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+    for _ in 0..500 {
+        let mut change: f64 = 0.;
+        for i in 0..deg {
+            // p(z_i) / prod(z_i - z_j)
+            let z = roots[i];
+            let mut value = (1., 0.);
+            for c in monic.iter().skip(1) {
+                value = complex_mul(value, z);
+                value.0 += c;
+            }
+            let mut denominator = (1., 0.);
+            for (j, other) in roots.iter().enumerate() {
+                if j != i {
+                    denominator = complex_mul(denominator, (z.0 - other.0, z.1 - other.1));
+                }
+            }
+            let step = complex_div(value, denominator);
+            roots[i] = (z.0 - step.0, z.1 - step.1);
+            change = change.max(step.0.hypot(step.1));
+        }
+        if change <= 1e-14 * radius {
+            break;
+        }
+    }
+    roots
+}
+
+fn complex_mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn complex_div(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let norm = b.0 * b.0 + b.1 * b.1;
+    ((a.0 * b.0 + a.1 * b.1) / norm, (a.1 * b.0 - a.0 * b.1) / norm)
+}
@@ -0,0 +1,147 @@
+use std::f64::consts::PI;
+
+use crate::{Interval, error_analysis::golden_section_max, functions::TargetFunction, integral::Quadrature, legendre::{calculate_lambdas, clenshaw, legendre_values}, linalg::solve};
+
+/// The iteration stops once the largest error exceeds the levelled error by less than this fraction
+const RELATIVE_TOLERANCE: f64 = 1e-6;
+
+/// Result of the Remez exchange algorithm
+#[derive(Debug, Clone)]
+pub struct MinimaxApproximation {
+    /// Legendre coefficients of the polynomial, like the ones of `calculate_lambdas`
+    pub lambdas: Vec<f64>,
+    /// E of the last reference, the error f - p alternates between +E and -E in its points
+    pub levelled_error: f64,
+    /// Largest |f - p| found on the interval
+    pub max_error: f64,
+    /// Alternation points in x, poly_deg + 2 of them
+    pub reference: Vec<f64>,
+    pub iterations: usize,
+    /// false when max_iterations ran out or the reference couldn't be exchanged
+    pub converged: bool,
+}
+
+/// Returns the best uniform (minimax) approximation of degree poly_deg with the Remez exchange algorithm.
+/// Every step solves
+/// ### sum(lambda_k * L_k(t_i)) + (-1)^i E = f(x(t_i)), i = 0..poly_deg+1
+/// for the current reference t_i and replaces the reference by the alternating extrema of the new error.
+/// The first reference are the extrema of the error of the least-squares lambdas from `calculate_lambdas`,
+/// or the Chebyshev extrema when that error doesn't alternate often enough.
+/// By the equioscillation theorem the result is optimal once the largest error equals |E|.
+/// https://en.wikipedia.org/wiki/Remez_algorithm
+/// * quadrature, analytic_norm - used for the least-squares seed, see `calculate_lambdas`
+pub fn remez(f: &dyn TargetFunction, poly_deg: usize, interval: Interval, quadrature: &dyn Quadrature, analytic_norm: bool, max_iterations: usize) -> MinimaxApproximation {
+    let count = poly_deg + 2;
+    let target = |t: f64| f.value(interval.from_unit(t));
+    let grid = dense_grid(50 * count + 1000);
+    let scale = grid.iter().fold(0., |max: f64, t| max.max(target(*t).abs()));
+
+    let mut lambdas = calculate_lambdas(f, poly_deg, interval, quadrature, analytic_norm);
+    let mut reference = alternating_extrema(&|t| target(t) - clenshaw(&lambdas, t), &grid, count)
+        .unwrap_or_else(|| (0..count).map(|i| -(PI * i as f64 / (count - 1) as f64).cos()).collect());
+    let mut levelled_error = 0.;
+    let mut max_error = grid.iter().fold(0., |max: f64, t| max.max((target(*t) - clenshaw(&lambdas, *t)).abs()));
+    // f is a polynomial of degree poly_deg, the error is rounding noise without alternation
+    let noise = 100. * f64::EPSILON * scale;
+    let mut converged = max_error <= noise;
+    let mut iterations = 0;
+
+    while !converged && iterations < max_iterations {
+        iterations += 1;
+        let matrix: Vec<Vec<f64>> = reference
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let mut row = legendre_values(poly_deg, *t);
+                row.push(if i % 2 == 0 { 1. } else { -1. });
+                row
+            })
+            .collect();
+        let rhs: Vec<f64> = reference.iter().map(|t| target(*t)).collect();
+        let solution = match solve(matrix, rhs) {
+            Some(solution) => solution,
+            None => break,
+        };
+        lambdas = solution[..=poly_deg].to_vec();
+        levelled_error = solution[poly_deg + 1];
+
+        let error = |t: f64| target(t) - clenshaw(&lambdas, t);
+        let next = alternating_extrema(&error, &grid, count);
+        max_error = match &next {
+            Some(next) => next.iter().fold(0., |max: f64, t| max.max(error(*t).abs())),
+            None => grid.iter().fold(0., |max: f64, t| max.max(error(*t).abs())),
+        };
+        if max_error - levelled_error.abs() <= RELATIVE_TOLERANCE * max_error + noise {
+            converged = true;
+            break;
+        }
+        match next {
+            Some(next) => reference = next,
+            None => break,
+        }
+    }
+
+    MinimaxApproximation {
+        lambdas,
+        levelled_error: levelled_error.abs(),
+        max_error,
+        reference: reference.iter().map(|t| interval.from_unit(*t)).collect(),
+        iterations,
+        converged,
+    }
+}
+
+/// Points of [-1, 1] clustered towards the ends like the Chebyshev extrema,
+/// where the error of a polynomial approximation changes fastest
+fn dense_grid(points: usize) -> Vec<f64> {
+    (0..points).map(|i| -(PI * i as f64 / (points - 1) as f64).cos()).collect()
+}
+
+/// Finds `count` points where the error alternates in sign and is extremal.
+/// The grid is split into runs of equal sign, the largest |error| of every run is refined
+/// with a golden section search between its grid neighbours, and runs are dropped from
+/// the end with the smaller |error| until `count` remain.
+/// Returns None when the error changes sign fewer than count - 1 times.
+fn alternating_extrema(error: &dyn Fn(f64) -> f64, grid: &[f64], count: usize) -> Option<Vec<f64>> {
+    let values: Vec<f64> = grid.iter().map(|t| error(*t)).collect();
+    // (grid index, sign) of the largest |error| of every run
+    let mut runs: Vec<(usize, f64)> = Vec::new();
+    for (i, value) in values.iter().enumerate() {
+        if *value == 0. {
+            continue;
+        }
+        let sign = value.signum();
+        match runs.last_mut() {
+            Some((best, run_sign)) if *run_sign == sign => {
+                if value.abs() > values[*best].abs() {
+                    *best = i;
+                }
+            },
+            _ => runs.push((i, sign)),
+        }
+    }
+
+    let mut extrema: Vec<(f64, f64)> = runs
+        .iter()
+        .map(|(i, sign)| {
+            let lo = grid[i.saturating_sub(1)];
+            let hi = grid[(i + 1).min(grid.len() - 1)];
+            let t = golden_section_max(&|t| sign * error(t), lo, hi);
+            // the search never reaches the ends of its bracket exactly
+            let t = if sign * error(t) >= sign * values[*i] { t } else { grid[*i] };
+            (t, error(t).abs())
+        })
+        .collect();
+
+    while extrema.len() > count {
+        if extrema[0].1 < extrema[extrema.len() - 1].1 {
+            extrema.remove(0);
+        } else {
+            extrema.pop();
+        }
+    }
+    match extrema.len() == count {
+        true => Some(extrema.iter().map(|(t, _)| *t).collect()),
+        false => None,
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{Interval, functions::TargetFunction2D, gauss::gauss_legendre_2d, legendre::{clenshaw, legendre_values}};
+
+/// Which products L_i(t) L_j(s) make up the approximation of degree n
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Truncation {
+    /// i + j <= n
+    Total,
+    /// i <= n and j <= n
+    Tensor,
+}
+
+impl Truncation {
+    pub fn keeps(&self, i: usize, j: usize, degree: usize) -> bool {
+        match self {
+            Truncation::Total => i + j <= degree,
+            Truncation::Tensor => i <= degree && j <= degree,
+        }
+    }
+}
+
+/// Legendre approximation of a surface on the rectangle x_interval x y_interval
+#[derive(Debug, Clone)]
+pub struct LegendreSurface {
+    /// lambdas[i][j] belongs to L_i(t(x)) L_j(s(y)), zero for the products the truncation drops
+    pub lambdas: Vec<Vec<f64>>,
+    pub truncation: Truncation,
+    pub x_interval: Interval,
+    pub y_interval: Interval,
+}
+
+impl LegendreSurface {
+    /// Value in point (x, y), Clenshaw's recurrence in y for every i and once more in x
+    pub fn value(&self, x: f64, y: f64) -> f64 {
+        let s = self.y_interval.to_unit(y);
+        let columns: Vec<f64> = self.lambdas.iter().map(|row| clenshaw(row, s)).collect();
+        clenshaw(&columns, self.x_interval.to_unit(x))
+    }
+
+    /// Number of coefficients kept by the truncation
+    pub fn terms(&self) -> usize {
+        let degree = self.lambdas.len() - 1;
+        (0..=degree).map(|i| (0..=degree).filter(|j| self.truncation.keeps(i, *j, degree)).count()).sum()
+    }
+}
+
+/// calculates the lambdas of the tensor-product Legendre approximation of f
+/// ### lambda_ij = (2i + 1)(2j + 1) / 4 * integral of f(x(t), y(s)) L_i(t) L_j(s) over [-1, 1]^2
+/// with the n x n tensor Gauss-Legendre rule. The double sum is separable, so it is done
+/// as two one-dimensional sums over the nodes.
+/// * degree - largest degree in each variable, or of the products for `Truncation::Total`
+/// * nodes - Gauss nodes per variable
+pub fn calculate_lambdas_2d(f: &dyn TargetFunction2D, degree: usize, x_interval: Interval, y_interval: Interval, truncation: Truncation, nodes: usize) -> LegendreSurface {
+    let rule = gauss_legendre_2d(nodes);
+    // the rule lists the s nodes for every t node, so the first nodes entries hold all of them
+    let s_nodes: Vec<f64> = rule.iter().take(nodes).map(|node| node.1).collect();
+    let t_nodes: Vec<f64> = rule.iter().step_by(nodes).map(|node| node.0).collect();
+    let t_values: Vec<Vec<f64>> = t_nodes.iter().map(|t| legendre_values(degree, *t)).collect();
+    let s_values: Vec<Vec<f64>> = s_nodes.iter().map(|s| legendre_values(degree, *s)).collect();
+
+    // g[a][j] = sum over b of w_ab f(t_a, s_b) L_j(s_b)
+    let mut g = vec![vec![0.; degree + 1]; nodes];
+    for (index, (t, s, w)) in rule.iter().enumerate() {
+        let (a, b) = (index / nodes, index % nodes);
+        let value = w * f.value(x_interval.from_unit(*t), y_interval.from_unit(*s));
+        for (j, l) in s_values[b].iter().enumerate() {
+            g[a][j] += value * l;
+        }
+    }
+
+    let lambdas: Vec<Vec<f64>> = (0..=degree)
+        .map(|i| {
+            (0..=degree)
+                .map(|j| match truncation.keeps(i, j, degree) {
+                    true => {
+                        let sum: f64 = (0..nodes).map(|a| g[a][j] * t_values[a][i]).sum();
+                        (2 * i + 1) as f64 * (2 * j + 1) as f64 / 4. * sum
+                    },
+                    false => 0.,
+                })
+                .collect()
+        })
+        .collect();
+    LegendreSurface { lambdas, truncation, x_interval, y_interval }
+}